@@ -1,14 +1,40 @@
+use std::ops::RangeInclusive;
+
 pub fn is_armstrong_number(num: u32) -> bool {
-    let num_of_digits: u32 = ((num as f32).log10().floor() as u32) + 1;
+    // single digit numbers, including 0, are always their own first power
+    if num < 10 {
+        return true;
+    }
+    let num_of_digits: u32 = num.ilog10() + 1;
     let mut sum: u32 = 0;
     let mut temp: u32 = num;
     while temp != 0 {
         let digit: u32 = temp % 10;
-        sum = match digit.checked_pow(num_of_digits).and_then(|d: u32| sum.checked_add(d)) {
+        sum = match digit
+            .checked_pow(num_of_digits)
+            .and_then(|d: u32| sum.checked_add(d))
+        {
             Some(v) => v,
             None => return false,
         };
         temp /= 10;
     }
     sum == num
-}
\ No newline at end of file
+}
+
+/// Counts the Armstrong numbers in `range` without collecting them.
+pub fn count_armstrong_in(range: RangeInclusive<u32>) -> usize {
+    range.filter(|&num| is_armstrong_number(num)).count()
+}
+
+/// Returns every Armstrong number with exactly `d` digits, in ascending order.
+///
+/// Panics if `d` is not between 1 and 10, the most digits a `u32` holds.
+pub fn armstrong_numbers_with_digits(d: u32) -> Vec<u32> {
+    assert!((1..=10).contains(&d), "A u32 has between 1 and 10 digits");
+    let low = if d == 1 { 0 } else { 10u64.pow(d - 1) };
+    let high = (10u64.pow(d) - 1).min(u32::MAX as u64);
+    (low as u32..=high as u32)
+        .filter(|&num| is_armstrong_number(num))
+        .collect()
+}
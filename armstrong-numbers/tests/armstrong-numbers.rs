@@ -79,3 +79,37 @@ fn ten_digit_non_armstrong_number() {
 fn properly_handles_overflow() {
     assert!(!is_armstrong_number(4_106_098_957));
 }
+
+#[test]
+fn test_zero_and_single_digits_are_armstrong_numbers() {
+    assert!(is_armstrong_number(0));
+    for num in 1..=9 {
+        assert!(is_armstrong_number(num), "{} is an Armstrong number", num);
+    }
+}
+
+#[test]
+fn test_digit_count_at_powers_of_ten() {
+    // a float log10 rounds 999_999_999 up to 10 digits
+    assert!(!is_armstrong_number(10));
+    assert!(!is_armstrong_number(999_999_999));
+    assert!(!is_armstrong_number(1_000_000_000));
+    assert!(!is_armstrong_number(u32::MAX));
+}
+
+#[test]
+fn test_count_armstrong_in() {
+    // 0 to 9, 153, 370, 371 and 407
+    assert_eq!(count_armstrong_in(0..=1000), 14);
+    assert_eq!(count_armstrong_in(154..=369), 0);
+}
+
+#[test]
+fn test_armstrong_numbers_with_digits() {
+    assert_eq!(armstrong_numbers_with_digits(3), vec![153, 370, 371, 407]);
+    assert_eq!(
+        armstrong_numbers_with_digits(1),
+        (0..=9).collect::<Vec<u32>>()
+    );
+    assert_eq!(armstrong_numbers_with_digits(2), vec![]);
+}
@@ -1,8 +1,16 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
+use std::error::Error;
 use std::fmt;
 use std::ops::Add;
+use std::str::FromStr;
+use std::sync::OnceLock;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg(feature = "rand")]
+use rand::{seq::SliceRandom, Rng};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CardSuite {
     Clubs,
     Diamonds,
@@ -11,19 +19,54 @@ pub enum CardSuite {
 }
 
 impl CardSuite {
+    /// The four suites: Clubs, Diamonds, Hearts and Spades, in that order.
+    pub fn all() -> impl Iterator<Item = CardSuite> {
+        [
+            CardSuite::Clubs,
+            CardSuite::Diamonds,
+            CardSuite::Hearts,
+            CardSuite::Spades,
+        ]
+        .into_iter()
+    }
+
     pub fn from_string(suite_str: &str) -> Self {
-        match suite_str {
-            "C" => CardSuite::Clubs,
-            "D" => CardSuite::Diamonds,
-            "H" => CardSuite::Hearts,
-            "S" => CardSuite::Spades,
-            _ => panic!("Invalid suite string"),
+        CardSuite::parse(suite_str).expect("Invalid suite string")
+    }
+
+    /// The unicode symbol of the suite, e.g. '♥' for Hearts.
+    pub fn symbol(&self) -> char {
+        match self {
+            CardSuite::Clubs => '♣',
+            CardSuite::Diamonds => '♦',
+            CardSuite::Hearts => '♥',
+            CardSuite::Spades => '♠',
+        }
+    }
+
+    /// Diamonds and Hearts are red, Clubs and Spades are black.
+    pub fn is_red(&self) -> bool {
+        matches!(self, CardSuite::Diamonds | CardSuite::Hearts)
+    }
+
+    fn parse(suite_str: &str) -> Option<Self> {
+        match suite_str.to_ascii_uppercase().as_str() {
+            "C" | "♣" => Some(CardSuite::Clubs),
+            "D" | "♦" => Some(CardSuite::Diamonds),
+            "H" | "♥" => Some(CardSuite::Hearts),
+            "S" | "♠" => Some(CardSuite::Spades),
+            _ => None,
         }
     }
 }
 
+/// With the alternate flag, e.g. `{:#}`, renders the unicode `symbol` of the suite instead
+/// of its letter.
 impl fmt::Display for CardSuite {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            return write!(f, "{}", self.symbol());
+        }
         match *self {
             CardSuite::Clubs => write!(f, "C"),
             CardSuite::Diamonds => write!(f, "D"),
@@ -33,7 +76,24 @@ impl fmt::Display for CardSuite {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Serializes as the letter of the suite, e.g. "H".
+#[cfg(feature = "serde")]
+impl Serialize for CardSuite {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for CardSuite {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let suite_str = String::deserialize(deserializer)?;
+        CardSuite::parse(&suite_str)
+            .ok_or_else(|| de::Error::custom(format!("Invalid suite string: \"{}\"", suite_str)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CardRank {
     LowAce = 1,
     Two = 2,
@@ -52,49 +112,88 @@ pub enum CardRank {
 }
 
 impl CardRank {
+    /// The thirteen ranks of the cards in a deck, from Two up to Ace. An Ace played low is
+    /// not a rank of its own, so `CardRank::LowAce` is left out.
+    pub fn all() -> impl Iterator<Item = CardRank> {
+        (2..=14).map(CardRank::from_number)
+    }
+
     pub fn as_number(&self) -> u8 {
         *self as u8
     }
 
+    /// Numbers outside of 1 to 14 wrap around modulo 14, e.g. 15 is a LowAce again and 0 is
+    /// an Ace. Use `CardRank::try_from` to reject them instead.
     pub fn from_number(n: u8) -> Self {
-        // let value = ((n - 2) % 13) + 2; // use mod 13 to wrap around // If there is no LowAce
-        let value = ((n - 1) % 14) + 1; // use mod 14 to wrap around // with LowAce
-        match value {
-            1 => CardRank::LowAce,
-            2 => CardRank::Two,
-            3 => CardRank::Three,
-            4 => CardRank::Four,
-            5 => CardRank::Five,
-            6 => CardRank::Six,
-            7 => CardRank::Seven,
-            8 => CardRank::Eight,
-            9 => CardRank::Nine,
-            10 => CardRank::Ten,
-            11 => CardRank::Jack,
-            12 => CardRank::Queen,
-            13 => CardRank::King,
-            14 => CardRank::Ace,
-            _ => unreachable!(),
-        }
+        // use mod 14 to wrap around, with LowAce
+        let value = ((n as u16 + 13) % 14 + 1) as u8;
+        CardRank::try_from(value).expect("Expected a wrapped rank number")
     }
 
     pub fn from_string(rank_str: &str) -> Self {
         match rank_str {
             "1" => CardRank::LowAce, // note that this is not a real rank string, just a way to initialize a LowAce
-            "2" => CardRank::Two,
-            "3" => CardRank::Three,
-            "4" => CardRank::Four,
-            "5" => CardRank::Five,
-            "6" => CardRank::Six,
-            "7" => CardRank::Seven,
-            "8" => CardRank::Eight,
-            "9" => CardRank::Nine,
-            "10" => CardRank::Ten,
-            "J" => CardRank::Jack,
-            "Q" => CardRank::Queen,
-            "K" => CardRank::King,
-            "A" => CardRank::Ace,
-            _ => panic!("Invalid rank string"),
+            _ => CardRank::parse(rank_str).expect("Invalid rank string"),
+        }
+    }
+
+    fn parse(rank_str: &str) -> Option<Self> {
+        match rank_str.to_ascii_uppercase().as_str() {
+            "2" => Some(CardRank::Two),
+            "3" => Some(CardRank::Three),
+            "4" => Some(CardRank::Four),
+            "5" => Some(CardRank::Five),
+            "6" => Some(CardRank::Six),
+            "7" => Some(CardRank::Seven),
+            "8" => Some(CardRank::Eight),
+            "9" => Some(CardRank::Nine),
+            "10" | "T" => Some(CardRank::Ten),
+            "J" => Some(CardRank::Jack),
+            "Q" => Some(CardRank::Queen),
+            "K" => Some(CardRank::King),
+            "A" => Some(CardRank::Ace),
+            _ => None,
+        }
+    }
+
+    // LowAce is just an ace played low, so physically it is the same rank as Ace
+    fn ace_high(self) -> Self {
+        match self {
+            CardRank::LowAce => CardRank::Ace,
+            rank => rank,
+        }
+    }
+
+    fn ace_low(self) -> Self {
+        match self {
+            CardRank::Ace => CardRank::LowAce,
+            rank => rank,
+        }
+    }
+
+    // The rank spelled out, as used in hand descriptions.
+    fn name(self) -> &'static str {
+        match self {
+            CardRank::LowAce | CardRank::Ace => "Ace",
+            CardRank::Two => "Two",
+            CardRank::Three => "Three",
+            CardRank::Four => "Four",
+            CardRank::Five => "Five",
+            CardRank::Six => "Six",
+            CardRank::Seven => "Seven",
+            CardRank::Eight => "Eight",
+            CardRank::Nine => "Nine",
+            CardRank::Ten => "Ten",
+            CardRank::Jack => "Jack",
+            CardRank::Queen => "Queen",
+            CardRank::King => "King",
+        }
+    }
+
+    fn plural_name(self) -> String {
+        match self {
+            CardRank::Six => "Sixes".to_string(),
+            rank => format!("{}s", rank.name()),
         }
     }
 }
@@ -120,6 +219,60 @@ impl fmt::Display for CardRank {
     }
 }
 
+/// Serializes as the rank of a card string, e.g. "10", so a LowAce comes back as an Ace.
+#[cfg(feature = "serde")]
+impl Serialize for CardRank {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for CardRank {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rank_str = String::deserialize(deserializer)?;
+        CardRank::parse(&rank_str)
+            .ok_or_else(|| de::Error::custom(format!("Invalid rank string: \"{}\"", rank_str)))
+    }
+}
+
+/// Accepts 1 for a LowAce up to 14 for an Ace.
+impl TryFrom<u8> for CardRank {
+    type Error = InvalidRankNumber;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        match n {
+            1 => Ok(CardRank::LowAce),
+            2 => Ok(CardRank::Two),
+            3 => Ok(CardRank::Three),
+            4 => Ok(CardRank::Four),
+            5 => Ok(CardRank::Five),
+            6 => Ok(CardRank::Six),
+            7 => Ok(CardRank::Seven),
+            8 => Ok(CardRank::Eight),
+            9 => Ok(CardRank::Nine),
+            10 => Ok(CardRank::Ten),
+            11 => Ok(CardRank::Jack),
+            12 => Ok(CardRank::Queen),
+            13 => Ok(CardRank::King),
+            14 => Ok(CardRank::Ace),
+            _ => Err(InvalidRankNumber(n)),
+        }
+    }
+}
+
+/// A number which isn't the number of any rank, see `CardRank::try_from`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidRankNumber(pub u8);
+
+impl fmt::Display for InvalidRankNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Rank numbers go from 1 to 14, got {}", self.0)
+    }
+}
+
+impl Error for InvalidRankNumber {}
+
 impl Ord for CardRank {
     fn cmp(&self, other: &Self) -> Ordering {
         (*self as u8).cmp(&(*other as u8))
@@ -150,73 +303,328 @@ impl Add<CardRank> for CardRank {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq)]
+/// Cards are equal when both their rank and suite match, see `Card::same_rank` for
+/// comparing the ranks alone. They are ordered by rank and then by suite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Card {
     rank: CardRank,
     suite: CardSuite,
 }
 
-impl PartialEq for Card {
-    fn eq(&self, other: &Self) -> bool {
-        self.rank == other.rank
-    }
-}
-
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.rank.cmp(&other.rank))
+        Some(
+            self.rank
+                .cmp(&other.rank)
+                .then_with(|| (self.suite as u8).cmp(&(other.suite as u8))),
+        )
     }
 }
 
 impl Card {
     pub fn get_highest_card(cards: &[Card]) -> Card {
         let mut cards = cards.to_vec();
-        cards.sort_by(|a, b| a.partial_cmp(&b).unwrap_or(Ordering::Equal));
-        cards.last().unwrap().clone()
+        cards.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        *cards.last().unwrap()
     }
 
     pub fn rank(&self) -> &CardRank {
         &self.rank
     }
 
-    pub fn new(card_str: &str) -> Self {
-        assert!(
-            card_str.len() >= 2,
-            "Card string must be at least 2 characters long"
-        );
+    pub fn suite(&self) -> &CardSuite {
+        &self.suite
+    }
+
+    /// Whether both cards have the same rank, whatever their suites.
+    pub fn same_rank(&self, other: &Card) -> bool {
+        self.rank == other.rank
+    }
 
-        let rank_str = &card_str[..(card_str.len() - 1)];
-        let suite_str = &card_str[(card_str.len() - 1)..];
+    // Two cards are the same physical card when both rank and suite match.
+    fn is_same_card(&self, other: &Card) -> bool {
+        self.rank.ace_high() == other.rank.ace_high() && self.suite == other.suite
+    }
 
-        let rank = CardRank::from_string(rank_str);
-        let suite = CardSuite::from_string(suite_str);
+    /// The index of the card in the tables of the "2+2" lookup evaluator, from 1 for the
+    /// Two of Clubs up to 52 for the Ace of Spades, ranks first and then Clubs, Diamonds,
+    /// Hearts and Spades.
+    pub fn to_twoplustwo_index(&self) -> u8 {
+        (self.rank.ace_high().as_number() - 2) * 4 + self.suite as u8 + 1
+    }
 
+    /// Builds a card straight out of its rank and suite, without going through a string.
+    pub fn from_parts(rank: CardRank, suite: CardSuite) -> Self {
         Card { rank, suite }
     }
+
+    pub fn new(card_str: &str) -> Self {
+        Card::try_new(card_str).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// The card string right-padded with spaces to `width` characters, so that "10" cards
+    /// line up with the others.
+    pub fn to_padded_string(&self, width: usize) -> String {
+        format!("{:<width$}", self.to_string())
+    }
+
+    /// Parses a card string made of exactly a rank followed by a single suite, e.g. "10H".
+    /// Tens may also be written as "T", and suites as their unicode symbol, e.g. "T♥". Letters
+    /// may be of either case. There is no "1" rank for an Ace played low, so "1H" is an
+    /// `InvalidRank`.
+    pub fn try_new(card_str: &str) -> Result<Self, CardParseError> {
+        // "10" is the only rank made of more than one character
+        let rank_len = if card_str.starts_with("10") {
+            2
+        } else {
+            card_str.chars().next().map_or(0, char::len_utf8)
+        };
+        let rank_str = &card_str[..rank_len];
+
+        let mut rest = card_str[rank_len..].chars();
+        let suite_str = match rest.next() {
+            Some(c) => c.to_string(),
+            None => return Err(CardParseError::TooShort(card_str.to_string())),
+        };
+
+        let rank = CardRank::parse(rank_str)
+            .ok_or_else(|| CardParseError::InvalidRank(card_str.to_string()))?;
+        let suite = CardSuite::parse(&suite_str)
+            .ok_or_else(|| CardParseError::InvalidSuite(card_str.to_string()))?;
+
+        if !rest.as_str().is_empty() {
+            return Err(CardParseError::TrailingCharacters(card_str.to_string()));
+        }
+
+        Ok(Card { rank, suite })
+    }
 }
 
+/// With the alternate flag, e.g. `{:#}`, renders the suite as a unicode symbol, e.g. "A♥".
 impl fmt::Display for Card {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}{}", self.rank, self.suite)
+        if f.alternate() {
+            write!(f, "{}{:#}", self.rank, self.suite)
+        } else {
+            write!(f, "{}{}", self.rank, self.suite)
+        }
+    }
+}
+
+/// Parses a card the same way as `Card::try_new`, so whitespace around it is rejected.
+impl FromStr for Card {
+    type Err = CardParseError;
+
+    fn from_str(card_str: &str) -> Result<Self, Self::Err> {
+        Card::try_new(card_str)
+    }
+}
+
+/// Serializes as the card string, e.g. "AH".
+#[cfg(feature = "serde")]
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let card_str = String::deserialize(deserializer)?;
+        card_str.parse().map_err(de::Error::custom)
+    }
+}
+
+/// The reasons why a card string can't be parsed. Each variant holds the offending string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardParseError {
+    /// There are not enough characters for both a rank and a suite.
+    TooShort(String),
+    InvalidRank(String),
+    InvalidSuite(String),
+    /// There are more characters after the suite.
+    TrailingCharacters(String),
+}
+
+impl fmt::Display for CardParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CardParseError::TooShort(s) => write!(
+                f,
+                "Card string must be at least 2 characters long: \"{}\"",
+                s
+            ),
+            CardParseError::InvalidRank(s) => write!(f, "Invalid rank string: \"{}\"", s),
+            CardParseError::InvalidSuite(s) => write!(f, "Invalid suite string: \"{}\"", s),
+            CardParseError::TrailingCharacters(s) => {
+                write!(f, "Unexpected characters after the suite: \"{}\"", s)
+            }
+        }
+    }
+}
+
+impl Error for CardParseError {}
+
+impl From<Card> for (CardRank, CardSuite) {
+    fn from(card: Card) -> Self {
+        (card.rank, card.suite)
+    }
+}
+
+impl From<(CardRank, CardSuite)> for Card {
+    fn from((rank, suite): (CardRank, CardSuite)) -> Self {
+        Card::from_parts(rank, suite)
+    }
+}
+
+/// All 52 cards of a standard deck, ordered by suite and then by ascending rank.
+pub fn standard_deck() -> Vec<Card> {
+    CardSuite::all()
+        .flat_map(|suite| CardRank::all().map(move |rank| Card::from_parts(rank, suite)))
+        .collect()
+}
+
+/// Returns all the cards of a standard deck which are not in `known`, in deck order.
+pub fn unseen_cards(known: &[Card]) -> Vec<Card> {
+    standard_deck()
+        .into_iter()
+        .filter(|card| !known.iter().any(|known| known.is_same_card(card)))
+        .collect()
+}
+
+/// Whether `candidate` can join the cards of a hand being built, that is, the hand holds
+/// fewer than five cards and none of them is the same card as `candidate`.
+pub fn can_add(existing: &[Card], candidate: Card) -> bool {
+    existing.len() < 5 && !existing.iter().any(|card| card.is_same_card(&candidate))
+}
+
+/// The reasons why a set of cards can't come out of a given number of decks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultideckError {
+    /// The card appears this many times, more than there are decks.
+    TooManyCopies(Card, usize),
+}
+
+impl fmt::Display for MultideckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultideckError::TooManyCopies(card, copies) => {
+                write!(f, "Card {} appears {} times", card, copies)
+            }
+        }
+    }
+}
+
+impl Error for MultideckError {}
+
+/// Checks that the cards can be dealt out of `decks` standard decks, that is, no card
+/// appears more than `decks` times.
+pub fn validate_multideck(cards: &[Card], decks: usize) -> Result<(), MultideckError> {
+    for card in cards {
+        let copies = cards
+            .iter()
+            .filter(|other| other.is_same_card(card))
+            .count();
+        if copies > decks {
+            return Err(MultideckError::TooManyCopies(*card, copies));
+        }
     }
+    Ok(())
 }
 
 pub struct CardVec(Vec<Card>);
 
+/// Passes the alternate flag, e.g. `{:#}`, on to the cards.
 impl fmt::Display for CardVec {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let alternate = f.alternate();
         write!(
             f,
             "[{}]",
             self.0
                 .iter()
-                .map(|card| card.to_string())
+                .map(|card| match alternate {
+                    true => format!("{:#}", card),
+                    false => card.to_string(),
+                })
                 .collect::<Vec<String>>()
                 .join(", ")
         )
     }
 }
 
+/// A deck of cards which are dealt from the top.
+#[derive(Debug, Clone)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    /// A full standard deck, ordered by suite and then by ascending rank.
+    pub fn new() -> Self {
+        Deck {
+            cards: standard_deck(),
+        }
+    }
+
+    /// Parses the space separated cards written by the deck's `Display`, top card first.
+    pub fn from_string(deck_str: &str) -> Result<Self, CardParseError> {
+        let cards = deck_str
+            .split_whitespace()
+            .map(Card::try_new)
+            .collect::<Result<Vec<Card>, CardParseError>>()?;
+        Ok(Deck { cards })
+    }
+
+    /// Puts the cards in a random order drawn from `rng`.
+    #[cfg(feature = "rand")]
+    pub fn shuffle<R: Rng>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
+    }
+
+    /// Removes `n` cards from the top of the deck, or returns `None` if there are not enough left.
+    pub fn deal(&mut self, n: usize) -> Option<Vec<Card>> {
+        if n > self.cards.len() {
+            return None;
+        }
+        Some(self.cards.drain(..n).collect())
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+}
+
+/// The cards left in the deck, space separated and top card first.
+impl fmt::Display for Deck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.cards
+                .iter()
+                .map(|card| card.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        )
+    }
+}
+
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
 pub enum PokerHandRank {
     HighCard = 1,
@@ -253,121 +661,534 @@ impl PokerHandRank {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-#[allow(unused)]
-pub struct PokerHand<'a> {
-    rank: PokerHandRank,
-    cards: [Card; 5],
-    raw: &'a str,
-    pairs: Option<(Pair, Option<Pair>)>,
-    triplet: Option<Triplet>,
-    quadruplet: Option<Quadruplet>,
-    sequence: Option<Sequence>,
+impl fmt::Display for PokerHandRank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PokerHandRank::HighCard => "High Card",
+            PokerHandRank::OnePair => "One Pair",
+            PokerHandRank::TwoPairs => "Two Pairs",
+            PokerHandRank::ThreeOfAKind => "Three of a Kind",
+            PokerHandRank::Straight => "Straight",
+            PokerHandRank::Flush => "Flush",
+            PokerHandRank::FullHouse => "Full House",
+            PokerHandRank::FourOfAKind => "Four of a Kind",
+            PokerHandRank::StraightFlush => "Straight Flush",
+            PokerHandRank::RoyalFlush => "Royal Flush",
+        };
+        write!(f, "{}", name)
+    }
 }
 
-impl<'a> fmt::Display for PokerHand<'a> {
+/// Parses the names written by `Display`, e.g. "Full House", ignoring case.
+impl FromStr for PokerHandRank {
+    type Err = UnknownHandRank;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        (1..=10)
+            .map(PokerHandRank::from_number)
+            .find(|rank| rank.to_string().eq_ignore_ascii_case(name))
+            .ok_or_else(|| UnknownHandRank(name.to_string()))
+    }
+}
+
+/// A name which isn't the name of any hand category, see `PokerHandRank::from_str`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownHandRank(pub String);
+
+impl fmt::Display for UnknownHandRank {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "\"{}\"", self.raw)
+        write!(f, "Unknown hand category: \"{}\"", self.0)
     }
 }
 
-impl<'a> PartialEq for PokerHand<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        self.rank == other.rank
+impl Error for UnknownHandRank {}
+
+/// Serializes as the name of the category, e.g. "Full House".
+#[cfg(feature = "serde")]
+impl Serialize for PokerHandRank {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
     }
 }
 
-impl<'a> PartialOrd for PokerHand<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.rank.cmp(&other.rank))
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PokerHandRank {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(de::Error::custom)
     }
 }
 
-impl<'a> PokerHand<'a> {
-    /// This function creates a suite map from a hand of cards.
-    ///
-    /// The suite map is an array of 4 elements, each representing one of the four suites in a deck of cards.
-    /// The index of the array represents the suite (as a usize), and the value at that index represents the count of cards of that suite in the hand.
-    /// For example, if the array is [2, 1, 1, 1], it means there are 2 cards of the first suite, and 1 card each of the other three suites.
-    ///
-    /// This is called a "map" because it maps each suite to the count of cards of that suite.
-    fn get_suite_map(cards: [Card; 5]) -> [usize; 4] {
-        let mut suites = [0; 4];
-        for card in &cards {
-            let suite = card.suite as usize;
-            suites[suite] += 1;
+/// Which of the straights involving an Ace are recognized, as some simplified variants don't play them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StraightPolicy {
+    /// Whether the Ace to 5 straight (the wheel) is a straight.
+    pub allow_wheel: bool,
+    /// Whether the 10 to Ace straight (broadway) is a straight.
+    pub allow_broadway: bool,
+}
+
+impl Default for StraightPolicy {
+    fn default() -> Self {
+        Self {
+            allow_wheel: true,
+            allow_broadway: true,
         }
-        suites
     }
+}
 
-    /// This function creates a rank map from a hand of cards.
-    ///
-    /// The rank map is an array of 15 elements, each representing one of the 15 possible ranks in a deck of cards (1-14, with 1 and 14 both representing Ace).
-    /// The index of the array represents the rank (as a usize), and the value at that index represents the count of cards of that rank in the hand.
-    /// For example, if the array is [0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2], it means there are 2 Aces and no other cards.
-    ///
-    /// This is called a "map" because it maps each rank to the count of cards of that rank.
-    fn get_rank_map(cards: [Card; 5]) -> [usize; 15] {
-        let mut ranks = [0; 15];
-        for card in &cards {
-            let rank = card.rank.as_number() as usize;
-            ranks[rank] += 1;
-            if rank == 14 {
-                ranks[1] += 1; // Ace can also be considered as 1
-            }
+// The ranks of the Ace to 5 straight, with the Ace held as the highest rank.
+const WHEEL_BITS: u16 = 1 << 14 | 0b11_1100;
+
+// The ranks of the 10 to Ace straight.
+const BROADWAY_BITS: u16 = 0b1_1111 << 10;
+
+/// Classifies a five card hand without its cards, for callers keeping their own bitmasks.
+/// `rank_bits` has bit `n` set when a card of rank `n` (2 to 14, the Ace being 14) is held,
+/// and `rank_counts` holds how many cards there are of each rank, indexed the same way.
+/// Index 1 of the counts is ignored.
+pub fn classify_from_bits(rank_bits: u16, is_flush: bool, rank_counts: &[u8; 15]) -> PokerHandRank {
+    let counts = &rank_counts[2..];
+    let is_straight = PokerHand::is_straight(rank_bits);
+    let pairs = counts.iter().filter(|&&n| n == 2).count();
+
+    if is_flush && is_straight {
+        if rank_bits == BROADWAY_BITS {
+            return PokerHandRank::RoyalFlush;
         }
-        ranks
+        return PokerHandRank::StraightFlush;
     }
-
-    pub fn cards(&self) -> &[Card; 5] {
-        &self.cards
+    if counts.contains(&4) {
+        return PokerHandRank::FourOfAKind;
     }
-
-    fn is_flush(suite_map: [usize; 4]) -> bool {
-        suite_map.iter().any(|&s| s == 5)
+    if counts.contains(&3) && pairs == 1 {
+        return PokerHandRank::FullHouse;
     }
-
-    fn is_straight(rank_map: [usize; 15]) -> bool {
-        (rank_map
-            .windows(5)
-            .any(|window| window.iter().all(|&r| r == 1))
-            && !(rank_map[13] == 1 && rank_map[14] == 1 && rank_map[2] == 1)) // Not a straight if there is a King, an Ace, and a Two
-            || (rank_map[0..5].iter().all(|&r| r == 1) && rank_map[14] == 1) // Ace to 5 straight
+    if is_flush {
+        return PokerHandRank::Flush;
     }
-
-    fn is_high_sequence(rank_map: [usize; 15]) -> bool {
-        rank_map[10..15].iter().all(|&r| r == 1)
+    if is_straight {
+        return PokerHandRank::Straight;
     }
-
-    fn is_three_of_a_kind(ranks: &[usize; 15]) -> bool {
-        ranks.iter().any(|&r| r == 3)
+    if counts.contains(&3) {
+        return PokerHandRank::ThreeOfAKind;
     }
-
-    fn is_four_of_a_kind(ranks: &[usize; 15]) -> bool {
-        ranks.iter().any(|&r| r == 4)
+    match pairs {
+        2 => PokerHandRank::TwoPairs,
+        1 => PokerHandRank::OnePair,
+        _ => PokerHandRank::HighCard,
     }
+}
 
-    fn is_full_house(ranks: &[usize; 15]) -> bool {
-        PokerHand::is_three_of_a_kind(ranks) && PokerHand::is_one_pair(ranks)
-    }
+// A set of the ranks held, where bit `n` is set when a card of rank `n` (2 to 14) is held.
+fn rank_bitset(cards: &[Card]) -> u16 {
+    cards
+        .iter()
+        .fold(0, |bits, card| bits | 1 << card.rank.ace_high().as_number())
+}
 
-    fn is_two_pairs(ranks: &[usize; 15]) -> bool {
-        ranks.iter().filter(|&&r| r == 2).count() == 2
-    }
+/// The ranks appearing among any number of cards, each once and in ascending order, with
+/// the Ace high.
+pub fn distinct_ranks(cards: &[Card]) -> Vec<CardRank> {
+    let rank_bits = rank_bitset(cards);
+    CardRank::all()
+        .filter(|rank| rank_bits & 1 << rank.as_number() != 0)
+        .collect()
+}
 
-    fn is_one_pair(ranks: &[usize; 15]) -> bool {
-        ranks.iter().any(|&r| r == 2)
+// Sets bit 1 as well when an Ace is held, so that it also counts as the lowest rank.
+fn with_low_ace(rank_bits: u16) -> u16 {
+    if rank_bits & 1 << 14 != 0 {
+        rank_bits | 1 << 1
+    } else {
+        rank_bits
     }
+}
 
-    pub fn get_triplet(cards: [Card; 5]) -> Option<Triplet> {
-        let ranks = PokerHand::get_rank_map(cards);
-        if PokerHand::is_three_of_a_kind(&ranks) || PokerHand::is_full_house(&ranks) {
-            let mut rank = CardRank::from_number(ranks.iter().position(|&r| r == 3).unwrap() as u8);
-            if rank == CardRank::LowAce {
-                rank = CardRank::Ace;
-            }
-            let cards = cards
-                .iter()
+// Whether the ranks hold a run of `len` consecutive ranks, counting an Ace both high and low.
+fn has_rank_run(rank_bits: u16, len: u32) -> bool {
+    let rank_bits = with_low_ace(rank_bits);
+    let run = (1 << len) - 1;
+    (1..=15 - len).any(|low| rank_bits >> low & run == run)
+}
+
+/// Counts the distinct straights, from Ace to 5 up to 10 to Ace, holding at least three of
+/// the given ranks, that is, the straights two more cards can make, e.g. on a board.
+pub fn possible_straights(ranks: &[CardRank]) -> usize {
+    straight_windows(ranks, 3)
+}
+
+// Counts the five rank straight windows holding at least `held` of the ranks.
+fn straight_windows(ranks: &[CardRank], held: u32) -> usize {
+    let rank_bits = with_low_ace(
+        ranks
+            .iter()
+            .fold(0, |bits, rank| bits | 1 << rank.ace_high().as_number()),
+    );
+    (1..=10)
+        .filter(|low| (rank_bits >> low & 0b1_1111).count_ones() >= held)
+        .count()
+}
+
+/// Finds a double inside straight draw, where either of two ranks fills a different gap to
+/// make a straight, e.g. 5 7 8 9 J needing a 6 or a 10. Returns the two ranks, lowest first.
+/// Open-ended draws, where four consecutive ranks are already held, are not double gutshots.
+pub fn double_gutshot(cards: &[Card]) -> Option<[CardRank; 2]> {
+    let rank_bits = rank_bitset(cards);
+    if has_rank_run(rank_bits, 4) {
+        return None;
+    }
+    let outs: Vec<CardRank> = CardRank::all()
+        .filter(|rank| {
+            let bit = 1 << rank.as_number();
+            rank_bits & bit == 0 && has_rank_run(rank_bits | bit, 5)
+        })
+        .collect();
+    match outs[..] {
+        [low, high] => Some([low, high]),
+        _ => None,
+    }
+}
+
+/// The order of the categories, as some variants rank them differently, e.g. short deck
+/// where a flush beats a full house. Each category maps to an index, higher is stronger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryRanking {
+    indices: [usize; 10],
+}
+
+impl CategoryRanking {
+    pub fn index(&self, rank: PokerHandRank) -> usize {
+        self.indices[rank.as_number() as usize - 1]
+    }
+
+    /// Moves `rank` to `index`, leaving the other categories where they are.
+    pub fn with_index(mut self, rank: PokerHandRank, index: usize) -> Self {
+        self.indices[rank.as_number() as usize - 1] = index;
+        self
+    }
+}
+
+/// The standard order, from high card up to royal flush.
+impl Default for CategoryRanking {
+    fn default() -> Self {
+        Self {
+            indices: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+        }
+    }
+}
+
+/// Coarse buckets of hand strength, weakest first, see `PokerHand::strength_tier`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StrengthTier {
+    Trash,
+    Marginal,
+    Strong,
+    Premium,
+    Monster,
+}
+
+/// Which card ranks are wild, e.g. Twos in deuces wild. A wild card stands for any card.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WildConfig {
+    pub ranks: Vec<CardRank>,
+}
+
+impl WildConfig {
+    pub fn is_wild(&self, card: &Card) -> bool {
+        self.ranks
+            .iter()
+            .any(|rank| rank.ace_high() == card.rank.ace_high())
+    }
+}
+
+/// Everything about how a hand plays at once, see `PokerHand::summary`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandSummary {
+    pub rank: PokerHandRank,
+    /// The cards making the category, highest first.
+    pub scoring_cards: Vec<Card>,
+    /// The remaining cards, highest first.
+    pub kickers: Vec<Card>,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[allow(unused)]
+pub struct PokerHand<'a> {
+    rank: PokerHandRank,
+    cards: [Card; 5],
+    raw: Option<&'a str>,
+    pairs: Option<(Pair, Option<Pair>)>,
+    triplet: Option<Triplet>,
+    quadruplet: Option<Quadruplet>,
+    sequence: Option<Sequence>,
+}
+
+/// A hand which doesn't borrow its string, e.g. because it was made out of cards.
+pub type OwnedPokerHand = PokerHand<'static>;
+
+/// Passes the alternate flag, e.g. `{:#}`, on to the cards, which are then rendered in the
+/// order of the string the hand was made of, if any.
+impl<'a> fmt::Display for PokerHand<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            let cards: Vec<String> = match self.raw {
+                Some(raw) => raw
+                    .split_whitespace()
+                    .map(|card_str| format!("{:#}", Card::new(card_str)))
+                    .collect(),
+                None => self
+                    .cards
+                    .iter()
+                    .map(|card| format!("{:#}", card))
+                    .collect(),
+            };
+            return write!(f, "\"{}\"", cards.join(" "));
+        }
+        match self.raw {
+            Some(raw) => write!(f, "\"{}\"", raw),
+            None => write!(
+                f,
+                "\"{}\"",
+                self.cards
+                    .iter()
+                    .map(|card| card.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+        }
+    }
+}
+
+/// Hands are equal when they are as strong, that is, they would split the pot.
+impl<'a> PartialEq for PokerHand<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.compare_strength(other) == Ordering::Equal
+    }
+}
+
+impl<'a> Eq for PokerHand<'a> {}
+
+/// Orders hands by strength: by category first, and then by the ranks deciding ties within
+/// it, e.g. the pair and then the kickers. The Ace to 5 straight is the lowest straight.
+impl<'a> Ord for PokerHand<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare_strength(other)
+    }
+}
+
+impl<'a> PartialOrd for PokerHand<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> PokerHand<'a> {
+    /// This function creates a suite map from a hand of cards.
+    ///
+    /// The suite map is an array of 4 elements, each representing one of the four suites in a deck of cards.
+    /// The index of the array represents the suite (as a usize), and the value at that index represents the count of cards of that suite in the hand.
+    /// For example, if the array is [2, 1, 1, 1], it means there are 2 cards of the first suite, and 1 card each of the other three suites.
+    ///
+    /// This is called a "map" because it maps each suite to the count of cards of that suite.
+    fn get_suite_map(cards: &[Card]) -> [usize; 4] {
+        let mut suites = [0; 4];
+        for card in cards {
+            let suite = card.suite as usize;
+            suites[suite] += 1;
+        }
+        suites
+    }
+
+    /// This function creates a rank map from a hand of cards.
+    ///
+    /// The rank map is an array of 15 elements, each representing one of the 15 possible ranks in a deck of cards (1-14, with 1 and 14 both representing Ace).
+    /// The index of the array represents the rank (as a usize), and the value at that index represents the count of cards of that rank in the hand.
+    /// For example, if the array is [0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2], it means there are 2 Aces and no other cards.
+    ///
+    /// This is called a "map" because it maps each rank to the count of cards of that rank.
+    fn get_rank_map(cards: &[Card]) -> [usize; 15] {
+        let mut ranks = [0; 15];
+        for card in cards {
+            let rank = card.rank.as_number() as usize;
+            ranks[rank] += 1;
+            if rank == 14 {
+                ranks[1] += 1; // Ace can also be considered as 1
+            }
+        }
+        ranks
+    }
+
+    pub fn cards(&self) -> &[Card; 5] {
+        &self.cards
+    }
+
+    /// The category of the hand, e.g. `PokerHandRank::FullHouse`.
+    pub fn rank(&self) -> PokerHandRank {
+        self.rank
+    }
+
+    /// Draws the five cards as small boxes side by side, with the rank in the corners and the
+    /// suite symbol in the middle, five lines in total.
+    pub fn ascii_art(&self) -> String {
+        let row = |draw: &dyn Fn(&Card) -> String| {
+            self.cards
+                .iter()
+                .map(draw)
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        [
+            row(&|_| "+-----+".to_string()),
+            row(&|card| format!("|{:<2}   |", card.rank.to_string())),
+            row(&|card| format!("|  {}  |", card.suite.symbol())),
+            row(&|card| format!("|   {:>2}|", card.rank.to_string())),
+            row(&|_| "+-----+".to_string()),
+        ]
+        .join("\n")
+    }
+
+    /// How many of the cards are red and how many are black, as `(red, black)`.
+    pub fn color_split(&self) -> (usize, usize) {
+        let red = self.cards.iter().filter(|card| card.suite.is_red()).count();
+        (red, self.cards.len() - red)
+    }
+
+    /// The gap between the highest and the lowest rank of the hand, counting an Ace high.
+    pub fn rank_span(&self) -> u8 {
+        let rank_bits = rank_bitset(&self.cards);
+        let highest = u16::BITS - 1 - rank_bits.leading_zeros();
+        (highest - rank_bits.trailing_zeros()) as u8
+    }
+
+    /// Whether the five ranks are distinct and within a span of 4, the shape of a straight.
+    pub fn is_connected(&self) -> bool {
+        rank_bitset(&self.cards).count_ones() == 5 && self.rank_span() == 4
+    }
+
+    /// How many distinct suites appear in the hand, 1 for a flush and up to 4.
+    pub fn suit_count(&self) -> usize {
+        PokerHand::get_suite_map(&self.cards)
+            .iter()
+            .filter(|&&n| n > 0)
+            .count()
+    }
+
+    /// How many cards share each rank, biggest groups first, e.g. `[3, 2]` for a full house.
+    pub fn signature(&self) -> Vec<usize> {
+        // a card built as a LowAce is still an Ace, count it with the other Aces
+        let ranks = PokerHand::get_rank_map(&self.cards.map(|card| Card {
+            rank: card.rank.ace_high(),
+            suite: card.suite,
+        }));
+        let mut counts: Vec<usize> = ranks[2..].iter().copied().filter(|&n| n > 0).collect();
+        counts.sort_by_key(|&n| Reverse(n));
+        counts
+    }
+
+    /// Whether the hand holds this exact card, matching both rank and suite.
+    pub fn contains(&self, card: &Card) -> bool {
+        self.cards.iter().any(|held| held.is_same_card(card))
+    }
+
+    /// The hand with its suites relabelled in order of appearance, highest card first, so that
+    /// hands which only differ by a permutation of the suites share the same string, e.g.
+    /// "AA KA 7B 4C 2A".
+    pub fn suit_isomorph(&self) -> String {
+        let mut cards = self.cards.map(|card| Card {
+            rank: card.rank.ace_high(),
+            suite: card.suite,
+        });
+        cards.sort_by_key(|card| (Reverse(card.rank), card.suite as u8));
+
+        let mut seen: Vec<CardSuite> = vec![];
+        cards
+            .iter()
+            .map(|card| {
+                let label = match seen.iter().position(|&suite| suite == card.suite) {
+                    Some(index) => index,
+                    None => {
+                        seen.push(card.suite);
+                        seen.len() - 1
+                    }
+                };
+                format!("{}{}", card.rank, (b'A' + label as u8) as char)
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Whether both hands hold exactly the same physical cards, in any order.
+    pub fn same_cards(&self, other: &PokerHand) -> bool {
+        self.canonical_id() == other.canonical_id()
+    }
+
+    /// A stable identifier of the five physical cards, independent of the order they were
+    /// given in, e.g. "2C5D9HJSAC".
+    pub fn canonical_id(&self) -> String {
+        let mut cards = self.cards.map(|card| Card {
+            rank: card.rank.ace_high(),
+            suite: card.suite,
+        });
+        cards.sort_by_key(|card| (card.rank, card.suite as u8));
+        cards.iter().map(|card| card.to_string()).collect()
+    }
+
+    fn is_flush(suite_map: [usize; 4]) -> bool {
+        suite_map.contains(&5)
+    }
+
+    fn is_straight(rank_bits: u16) -> bool {
+        // five consecutive ranks, each held once
+        let consecutive = rank_bits.checked_shr(rank_bits.trailing_zeros()) == Some(0b1_1111);
+        consecutive || rank_bits == WHEEL_BITS
+    }
+
+    fn is_high_sequence(rank_map: [usize; 15]) -> bool {
+        rank_map[10..15].iter().all(|&r| r == 1)
+    }
+
+    // A straight holding both an Ace (high or low) and a Two can only be the Ace to 5 straight
+    fn is_low_sequence(rank_map: [usize; 15]) -> bool {
+        rank_map[1] == 1 && rank_map[2] == 1
+    }
+
+    fn is_straight_with_policy(cards: [Card; 5], policy: StraightPolicy) -> bool {
+        let rank_map = PokerHand::get_rank_map(&cards);
+        PokerHand::is_straight(rank_bitset(&cards))
+            && (policy.allow_wheel || !PokerHand::is_low_sequence(rank_map))
+            && (policy.allow_broadway || !PokerHand::is_high_sequence(rank_map))
+    }
+
+    fn is_three_of_a_kind(ranks: &[usize; 15]) -> bool {
+        ranks.contains(&3)
+    }
+
+    fn is_four_of_a_kind(ranks: &[usize; 15]) -> bool {
+        ranks.contains(&4)
+    }
+
+    fn is_full_house(ranks: &[usize; 15]) -> bool {
+        PokerHand::is_three_of_a_kind(ranks) && PokerHand::is_one_pair(ranks)
+    }
+
+    fn is_two_pairs(ranks: &[usize; 15]) -> bool {
+        // skip the LowAce slot, otherwise a pair of aces is counted twice
+        ranks[2..].iter().filter(|&&r| r == 2).count() == 2
+    }
+
+    fn is_one_pair(ranks: &[usize; 15]) -> bool {
+        ranks.contains(&2)
+    }
+
+    pub fn get_triplet(cards: [Card; 5]) -> Option<Triplet> {
+        let ranks = PokerHand::get_rank_map(&cards);
+        if PokerHand::is_three_of_a_kind(&ranks) || PokerHand::is_full_house(&ranks) {
+            // skip the LowAce slot, which holds the same count as the Ace slot
+            let rank =
+                CardRank::from_number((ranks[2..].iter().position(|&r| r == 3).unwrap() + 2) as u8);
+            let cards = cards
+                .iter()
                 .filter(|&card| card.rank == rank)
                 .cloned()
                 .collect::<Vec<Card>>();
@@ -381,12 +1202,11 @@ impl<'a> PokerHand<'a> {
     }
 
     pub fn get_quadruplet(cards: [Card; 5]) -> Option<Quadruplet> {
-        let ranks = PokerHand::get_rank_map(cards);
+        let ranks = PokerHand::get_rank_map(&cards);
         if PokerHand::is_four_of_a_kind(&ranks) {
-            let mut rank = CardRank::from_number(ranks.iter().position(|&r| r == 4).unwrap() as u8);
-            if rank == CardRank::LowAce {
-                rank = CardRank::Ace;
-            }
+            // skip the LowAce slot, which holds the same count as the Ace slot
+            let rank =
+                CardRank::from_number((ranks[2..].iter().position(|&r| r == 4).unwrap() + 2) as u8);
             let cards = cards
                 .iter()
                 .filter(|&card| card.rank == rank)
@@ -402,7 +1222,7 @@ impl<'a> PokerHand<'a> {
     }
 
     pub fn get_pairs(cards: [Card; 5]) -> Option<(Pair, Option<Pair>)> {
-        let ranks = PokerHand::get_rank_map(cards);
+        let ranks = PokerHand::get_rank_map(&cards);
 
         if PokerHand::is_two_pairs(&ranks) {
             let first_pair: Pair = {
@@ -420,10 +1240,7 @@ impl<'a> PokerHand<'a> {
             };
             let second_pair: Pair = {
                 let rank = CardRank::from_number(
-                    ranks
-                        .iter()
-                        .position(|&r| r == 2 && CardRank::from_number(r as u8) != first_pair.rank)
-                        .unwrap() as u8,
+                    (ranks[2..].iter().position(|&r| r == 2).unwrap() + 2) as u8,
                 );
                 let cards = cards
                     .iter()
@@ -438,7 +1255,8 @@ impl<'a> PokerHand<'a> {
             Some((first_pair, Some(second_pair)))
         } else if PokerHand::is_one_pair(&ranks) || PokerHand::is_full_house(&ranks) {
             let pair = {
-                let rank = CardRank::from_number(ranks.iter().position(|&r| r == 2).unwrap() as u8);
+                let rank =
+                    CardRank::from_number(ranks.iter().rposition(|&r| r == 2).unwrap() as u8);
                 let cards = cards
                     .iter()
                     .filter(|&card| card.rank == rank)
@@ -457,28 +1275,31 @@ impl<'a> PokerHand<'a> {
     }
 
     pub fn get_sequence(cards: [Card; 5]) -> Option<Sequence> {
-        let mut cards = cards.to_vec();
-        cards.sort_by(|a, b| a.rank.cmp(&b.rank));
-        let ranks =
-            PokerHand::get_rank_map(cards.clone().try_into().expect("Expected exactly 5 cards"));
-        if PokerHand::is_straight(ranks) {
-            let min_rank = CardRank::from_number(ranks.iter().position(|&r| r > 0).unwrap() as u8);
-            let max_rank = CardRank::from_number(ranks.iter().rposition(|&r| r > 0).unwrap() as u8);
+        PokerHand::get_sequence_with_policy(cards, StraightPolicy::default())
+    }
 
+    /// Like `get_sequence`, but only accepts the Ace to 5 and 10 to Ace straights if the policy allows them.
+    pub fn get_sequence_with_policy(cards: [Card; 5], policy: StraightPolicy) -> Option<Sequence> {
+        let ranks = PokerHand::get_rank_map(&cards);
+        let straight = PokerHand::is_straight_with_policy(cards, policy);
+        let mut cards = cards.to_vec();
+        cards.sort_by_key(|a| a.rank);
+        if straight {
             // LowAce
-            match (min_rank, max_rank) {
-                (CardRank::LowAce, CardRank::Ace) => {
-                    cards[4].rank = CardRank::LowAce;
-                    cards.sort_by(|a, b| a.rank.cmp(&b.rank));
-                    Some(Sequence {
-                        rank: CardRank::Five,
-                        cards: [cards[0], cards[1], cards[2], cards[3], cards[4]],
-                    })
+            if PokerHand::is_low_sequence(ranks) {
+                for card in cards.iter_mut() {
+                    card.rank = card.rank.ace_low();
                 }
-                _ => Some(Sequence {
-                    rank: max_rank,
+                cards.sort_by_key(|a| a.rank);
+                Some(Sequence {
+                    rank: CardRank::Five,
+                    cards: [cards[0], cards[1], cards[2], cards[3], cards[4]],
+                })
+            } else {
+                Some(Sequence {
+                    rank: cards[4].rank,
                     cards: [cards[0], cards[1], cards[2], cards[3], cards[4]],
-                }),
+                })
             }
         } else {
             None
@@ -486,11 +1307,16 @@ impl<'a> PokerHand<'a> {
     }
 
     pub fn get_rank(cards: [Card; 5]) -> PokerHandRank {
-        let suites = PokerHand::get_suite_map(cards);
-        let ranks = PokerHand::get_rank_map(cards);
+        PokerHand::get_rank_with_policy(cards, StraightPolicy::default())
+    }
+
+    /// Like `get_rank`, but only accepts the Ace to 5 and 10 to Ace straights if the policy allows them.
+    pub fn get_rank_with_policy(cards: [Card; 5], policy: StraightPolicy) -> PokerHandRank {
+        let suites = PokerHand::get_suite_map(&cards);
+        let ranks = PokerHand::get_rank_map(&cards);
 
         let is_flush = PokerHand::is_flush(suites);
-        let is_straight = PokerHand::is_straight(ranks);
+        let is_straight = PokerHand::is_straight_with_policy(cards, policy);
 
         if is_flush && is_straight {
             if PokerHand::is_high_sequence(ranks) {
@@ -531,6 +1357,36 @@ impl<'a> PokerHand<'a> {
         PokerHandRank::HighCard
     }
 
+    /// Like `get_rank`, but each wild card stands for whichever card makes the best category.
+    /// Five of a kind is not a category, so the wild cards never make one.
+    pub fn get_rank_wild(cards: [Card; 5], wild: &WildConfig) -> PokerHandRank {
+        let (wilds, naturals): (Vec<Card>, Vec<Card>) =
+            cards.iter().partition(|card| wild.is_wild(card));
+
+        // a flush can only be in the suite of the natural cards, so no other suite is worth trying
+        let mut suites: Vec<CardSuite> = naturals.iter().map(|card| card.suite).collect();
+        suites.sort_by_key(|&suite| suite as u8);
+        suites.dedup();
+        if suites.is_empty() {
+            suites.push(CardSuite::Spades);
+        }
+        let candidates: Vec<Card> = suites
+            .iter()
+            .flat_map(|&suite| CardRank::all().map(move |rank| Card::from((rank, suite))))
+            .collect();
+
+        card_multisets(&candidates, wilds.len())
+            .into_iter()
+            .map(|substitutes| -> [Card; 5] {
+                let cards: Vec<Card> = naturals.iter().chain(&substitutes).copied().collect();
+                cards.try_into().expect("Expected exactly 5 cards")
+            })
+            .filter(|&cards| !PokerHand::get_rank_map(&cards)[2..].contains(&5))
+            .map(PokerHand::get_rank)
+            .max()
+            .expect("Expected at least one substitution of the wild cards")
+    }
+
     fn cards_from_raw_string(raw: &'a str) -> [Card; 5] {
         let card_strs: Vec<&str> = raw.split_whitespace().collect();
         assert!(card_strs.len() == 5, "Hand string must contain 5 cards");
@@ -543,13 +1399,32 @@ impl<'a> PokerHand<'a> {
             Card::new(card_strs[4]),
         ];
 
-        cards.sort_by(|a, b| a.rank.cmp(&b.rank)); // sort ascending
+        cards.sort_by_key(|a| a.rank); // sort ascending
         cards
     }
 
     pub fn new(raw: &'a str) -> Self {
+        PokerHand::new_with_policy(raw, StraightPolicy::default())
+    }
+
+    fn new_with_policy(raw: &'a str, policy: StraightPolicy) -> Self {
         let cards: [Card; 5] = PokerHand::cards_from_raw_string(raw);
-        let rank = PokerHand::get_rank(cards);
+        PokerHand {
+            raw: Some(raw),
+            ..PokerHand::from_cards_with_policy(cards, policy)
+        }
+    }
+
+    /// Builds a hand straight out of its cards, without going through a string. Having no
+    /// string of its own, the hand displays its cards in ascending rank order instead, e.g.
+    /// `"2D 4S 4H 9D QC"`.
+    pub fn from_cards(cards: [Card; 5]) -> Self {
+        PokerHand::from_cards_with_policy(cards, StraightPolicy::default())
+    }
+
+    fn from_cards_with_policy(mut cards: [Card; 5], policy: StraightPolicy) -> Self {
+        cards.sort_by_key(|card| card.rank); // sort ascending
+        let rank = PokerHand::get_rank_with_policy(cards, policy);
         let pairs: Option<(Pair, Option<Pair>)>;
         let triplet: Option<Triplet>;
         let quadruplet: Option<Quadruplet>;
@@ -584,7 +1459,7 @@ impl<'a> PokerHand<'a> {
                 pairs = None;
                 triplet = None;
                 quadruplet = None;
-                sequence = PokerHand::get_sequence(cards);
+                sequence = PokerHand::get_sequence_with_policy(cards, policy);
             }
             PokerHandRank::Flush => {
                 pairs = None;
@@ -592,48 +1467,1220 @@ impl<'a> PokerHand<'a> {
                 quadruplet = None;
                 sequence = None;
             }
-            PokerHandRank::FullHouse => {
-                pairs = PokerHand::get_pairs(cards);
-                triplet = PokerHand::get_triplet(cards);
-                quadruplet = None;
-                sequence = None;
+            PokerHandRank::FullHouse => {
+                pairs = PokerHand::get_pairs(cards);
+                triplet = PokerHand::get_triplet(cards);
+                quadruplet = None;
+                sequence = None;
+            }
+            PokerHandRank::FourOfAKind => {
+                pairs = None;
+                triplet = None;
+                quadruplet = PokerHand::get_quadruplet(cards);
+                sequence = None;
+            }
+            PokerHandRank::StraightFlush => {
+                pairs = None;
+                triplet = None;
+                quadruplet = None;
+                sequence = PokerHand::get_sequence_with_policy(cards, policy);
+            }
+            PokerHandRank::RoyalFlush => {
+                pairs = None;
+                triplet = None;
+                quadruplet = None;
+                sequence = PokerHand::get_sequence_with_policy(cards, policy);
+            }
+        }
+
+        PokerHand {
+            raw: None,
+            rank,
+            cards,
+            pairs,
+            triplet,
+            quadruplet,
+            sequence,
+        }
+    }
+
+    // The ranks deciding between two hands of the same category, most significant first.
+    fn tiebreak_ranks(&self) -> Vec<CardRank> {
+        match self.rank {
+            PokerHandRank::Straight | PokerHandRank::StraightFlush | PokerHandRank::RoyalFlush => {
+                self.sequence.map(|seq| vec![seq.rank]).unwrap_or_default()
+            }
+            _ => {
+                // bigger groups of cards first, then higher ranks first
+                let ranks = PokerHand::get_rank_map(&self.cards);
+                let mut groups: Vec<(usize, CardRank)> = (2..15)
+                    .filter(|&n| ranks[n] > 0)
+                    .map(|n| (ranks[n], CardRank::from_number(n as u8)))
+                    .collect();
+                groups.sort_by(|a, b| b.cmp(a));
+                groups.into_iter().map(|(_, rank)| rank).collect()
+            }
+        }
+    }
+
+    // Splits the cards into those making the combination and the remaining kickers, both
+    // highest first. Every card of a straight, a flush or a full house is part of the
+    // combination, while every card of a high card hand is a kicker.
+    fn partition_cards(&self) -> (Vec<Card>, Vec<Card>) {
+        let mut cards = self.cards.to_vec();
+        cards.sort_by_key(|card| Reverse(card.rank.ace_high()));
+        match self.rank {
+            PokerHandRank::OnePair
+            | PokerHandRank::TwoPairs
+            | PokerHandRank::ThreeOfAKind
+            | PokerHandRank::FourOfAKind => {
+                let ranks = PokerHand::get_rank_map(&self.cards);
+                cards
+                    .into_iter()
+                    .partition(|card| ranks[card.rank.as_number() as usize] > 1)
+            }
+            PokerHandRank::HighCard => (vec![], cards),
+            _ => (cards, vec![]),
+        }
+    }
+
+    /// A one-line summary of the category and the ranks deciding it, e.g. "Full House, Aces
+    /// over Kings" or "Straight, Five high".
+    pub fn describe(&self) -> String {
+        let ranks = self.tiebreak_ranks();
+        let category = self.rank;
+        match category {
+            PokerHandRank::OnePair => format!("Pair of {}", ranks[0].plural_name()),
+            PokerHandRank::HighCard
+            | PokerHandRank::Straight
+            | PokerHandRank::Flush
+            | PokerHandRank::StraightFlush => format!("{}, {} high", category, ranks[0].name()),
+            PokerHandRank::TwoPairs => format!(
+                "{}, {} and {}",
+                category,
+                ranks[0].plural_name(),
+                ranks[1].plural_name()
+            ),
+            PokerHandRank::ThreeOfAKind | PokerHandRank::FourOfAKind => {
+                format!("{}, {}", category, ranks[0].plural_name())
+            }
+            PokerHandRank::FullHouse => format!(
+                "{}, {} over {}",
+                category,
+                ranks[0].plural_name(),
+                ranks[1].plural_name()
+            ),
+            PokerHandRank::RoyalFlush => category.to_string(),
+        }
+    }
+
+    /// The description as a single CamelCase word, e.g. "FlushAceHigh", "PairOfSixes" or
+    /// "FullHouseAcesOverKings". See `hole_cards_label` for the card notation solvers take.
+    pub fn class_label(&self) -> String {
+        self.describe()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                chars.next().map_or(String::new(), |first| {
+                    first.to_uppercase().chain(chars).collect()
+                })
+            })
+            .collect()
+    }
+
+    /// Buckets the hand by category: high card and pairs below Tens are `Trash`, pairs of
+    /// Tens or better are `Marginal`, two pairs and three of a kind are `Strong`, straights
+    /// and flushes are `Premium`, and full houses or better are `Monster`.
+    pub fn strength_tier(&self) -> StrengthTier {
+        match self.rank {
+            PokerHandRank::HighCard => StrengthTier::Trash,
+            PokerHandRank::OnePair if self.tiebreak_ranks()[0] < CardRank::Ten => {
+                StrengthTier::Trash
+            }
+            PokerHandRank::OnePair => StrengthTier::Marginal,
+            PokerHandRank::TwoPairs | PokerHandRank::ThreeOfAKind => StrengthTier::Strong,
+            PokerHandRank::Straight | PokerHandRank::Flush => StrengthTier::Premium,
+            PokerHandRank::FullHouse
+            | PokerHandRank::FourOfAKind
+            | PokerHandRank::StraightFlush
+            | PokerHandRank::RoyalFlush => StrengthTier::Monster,
+        }
+    }
+
+    /// The cards which are not part of the made combination, highest first. All five cards
+    /// of a high card hand are kickers, while straights, flushes and full houses have none.
+    pub fn kickers(&self) -> Vec<Card> {
+        self.partition_cards().1
+    }
+
+    /// The highest card which is not part of the pair, three or four of a kind. Straights,
+    /// flushes and full houses use all of their cards, so they have none.
+    pub fn top_kicker(&self) -> Option<Card> {
+        self.kickers().first().copied()
+    }
+
+    /// The category of the hand together with its scoring cards and kickers.
+    pub fn summary(&self) -> HandSummary {
+        let (scoring_cards, kickers) = self.partition_cards();
+        HandSummary {
+            rank: self.rank,
+            scoring_cards,
+            kickers,
+        }
+    }
+
+    // Compares two hands by category first and then by their tie-break ranks.
+    fn compare_strength(&self, other: &PokerHand) -> Ordering {
+        self.rank
+            .cmp(&other.rank)
+            .then_with(|| self.tiebreak_ranks().cmp(&other.tiebreak_ranks()))
+    }
+
+    /// A number which orders hands the same way as their strength: the category in the
+    /// highest bits, followed by 4 bits for each of the ranks deciding ties, most
+    /// significant first.
+    pub fn strength_key(&self) -> u32 {
+        let ranks = self.tiebreak_ranks();
+        (0..5).fold(self.rank.as_number() as u32, |key, i| {
+            key << 4 | ranks.get(i).map_or(0, |rank| rank.as_number() as u32)
+        })
+    }
+
+    /// How much stronger this hand is than `other`, by their `strength_key`. Positive means
+    /// this hand wins, zero means a tie.
+    pub fn strength_delta(&self, other: &PokerHand) -> i64 {
+        self.strength_key() as i64 - other.strength_key() as i64
+    }
+
+    /// The fraction of all the possible five card hands which are weaker than this one,
+    /// from 0.0 for the weakest 7 high to nearly 1.0 for a royal flush.
+    pub fn percentile(&self) -> f64 {
+        let classes = strength_classes();
+        let index =
+            classes.partition_point(|(class, _)| class.compare_strength(self) == Ordering::Less);
+        let weaker = classes.get(index).map_or(ALL_HANDS, |&(_, weaker)| weaker);
+        weaker as f64 / ALL_HANDS as f64
+    }
+
+    /// Whether the hand is stronger than the `threshold` fraction of all hands, e.g. 0.99 for
+    /// the top 1%.
+    pub fn is_monster(&self, threshold: f64) -> bool {
+        self.percentile() > threshold
+    }
+}
+
+/// Returns the weakest hand which can be made out of the cards neither in `target` nor in
+/// `dead`, and still beats `target`, or `None` if no such hand can be made.
+pub fn smallest_winner_over(target: &PokerHand, dead: &[Card]) -> Option<OwnedPokerHand> {
+    let known: Vec<Card> = target.cards.iter().chain(dead).copied().collect();
+    let unseen = unseen_cards(&known);
+    let classes = strength_classes();
+    let stronger =
+        classes.partition_point(|(class, _)| class.compare_strength(target) != Ordering::Greater);
+    classes[stronger..]
+        .iter()
+        .find_map(|(class, _)| realize_class(class, &unseen))
+}
+
+// A hand as strong as `class` made out of `cards`, if there is one.
+fn realize_class(class: &OwnedPokerHand, cards: &[Card]) -> Option<OwnedPokerHand> {
+    let ranks = PokerHand::get_rank_map(&class.cards.map(|card| Card {
+        rank: card.rank.ace_high(),
+        suite: card.suite,
+    }));
+
+    // every way of picking as many cards of each rank as the class holds
+    let mut hands: Vec<Vec<Card>> = vec![vec![]];
+    for n in (2..15).filter(|&n| ranks[n] > 0) {
+        let of_rank: Vec<Card> = cards
+            .iter()
+            .filter(|card| card.rank.ace_high().as_number() as usize == n)
+            .copied()
+            .collect();
+        let picks: Vec<Vec<Card>> = combinations(&of_rank, ranks[n]).collect();
+        hands = hands
+            .iter()
+            .flat_map(|hand| {
+                picks
+                    .iter()
+                    .map(move |pick| [hand.clone(), pick.clone()].concat())
+            })
+            .collect();
+    }
+
+    // a flush class needs a single suite, the others must not hold one
+    hands
+        .into_iter()
+        .map(|hand| PokerHand::from_cards(hand.try_into().expect("Expected exactly 5 cards")))
+        .find(|hand| hand.compare_strength(class) == Ordering::Equal)
+}
+
+// How many five card hands can be dealt out of a standard deck.
+const ALL_HANDS: u64 = 2_598_960;
+
+// One hand of each of the 7462 distinct strengths of a five card hand, weakest first, along
+// with how many of all the possible hands are weaker than it.
+fn strength_classes() -> &'static [(OwnedPokerHand, u64)] {
+    static CLASSES: OnceLock<Vec<(OwnedPokerHand, u64)>> = OnceLock::new();
+    CLASSES.get_or_init(|| {
+        let suites: Vec<CardSuite> = CardSuite::all().collect();
+        let ranks: Vec<Card> = CardRank::all()
+            .map(|rank| Card::from((rank, CardSuite::Spades)))
+            .collect();
+
+        // each class paired with how many physical hands fall into it
+        let mut classes: Vec<(OwnedPokerHand, u64)> = vec![];
+        for multiset in card_multisets(&ranks, 5) {
+            let counts: Vec<usize> = multiset
+                .iter()
+                .map(|card| {
+                    multiset
+                        .iter()
+                        .filter(|other| other.same_rank(card))
+                        .count()
+                })
+                .collect();
+            if counts.contains(&5) {
+                continue;
+            }
+
+            if counts.iter().all(|&count| count == 1) {
+                let offsuit: Vec<Card> = multiset
+                    .iter()
+                    .enumerate()
+                    .map(|(i, card)| Card {
+                        rank: card.rank,
+                        suite: suites[i % 4],
+                    })
+                    .collect();
+                let suited: [Card; 5] = multiset
+                    .clone()
+                    .try_into()
+                    .expect("Expected exactly 5 cards");
+                classes.push((
+                    PokerHand::from_cards(offsuit.try_into().expect("Expected exactly 5 cards")),
+                    1020,
+                ));
+                classes.push((PokerHand::from_cards(suited), 4));
+            } else {
+                // the n-th card of a rank takes the n-th suite, so no card is held twice
+                let cards: Vec<Card> = multiset
+                    .iter()
+                    .enumerate()
+                    .map(|(i, card)| Card {
+                        rank: card.rank,
+                        suite: suites[multiset[..i]
+                            .iter()
+                            .filter(|other| other.same_rank(card))
+                            .count()],
+                    })
+                    .collect();
+                // one group of each rank: choose `count` suites out of 4
+                let mut ways = 1;
+                for (i, card) in multiset.iter().enumerate() {
+                    if i == 0 || !multiset[i - 1].same_rank(card) {
+                        ways *= [1, 4, 6, 4, 1][counts[i]];
+                    }
+                }
+                classes.push((
+                    PokerHand::from_cards(cards.try_into().expect("Expected exactly 5 cards")),
+                    ways,
+                ));
+            }
+        }
+        classes.sort_by(|(a, _), (b, _)| a.compare_strength(b));
+
+        let mut weaker = 0;
+        for (_, count) in classes.iter_mut() {
+            let hands = *count;
+            *count = weaker;
+            weaker += hands;
+        }
+        classes
+    })
+}
+
+// Yields the combinations of `cards` in lexicographic order of their indices.
+struct Combinations<'a> {
+    cards: &'a [Card],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl Iterator for Combinations<'_> {
+    type Item = Vec<Card>;
+
+    fn next(&mut self) -> Option<Vec<Card>> {
+        if self.done {
+            return None;
+        }
+        let combination = self.indices.iter().map(|&i| self.cards[i]).collect();
+
+        // move the rightmost index which can still move, and reset the ones after it
+        let (n, k) = (self.cards.len(), self.indices.len());
+        match (0..k).rev().find(|&i| self.indices[i] < n - k + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+        Some(combination)
+    }
+}
+
+/// Lazily yields all the ways of picking `k` cards out of `cards`, keeping their relative order.
+pub fn combinations(cards: &[Card], k: usize) -> impl Iterator<Item = Vec<Card>> + '_ {
+    Combinations {
+        cards,
+        indices: (0..k).collect(),
+        done: k > cards.len(),
+    }
+}
+
+// All the ways of picking `k` cards out of `cards` when each card can be picked repeatedly.
+fn card_multisets(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    let Some((first, rest)) = cards.split_first() else {
+        return vec![];
+    };
+    let mut multisets: Vec<Vec<Card>> = card_multisets(cards, k - 1)
+        .into_iter()
+        .map(|mut multiset| {
+            multiset.insert(0, *first);
+            multiset
+        })
+        .collect();
+    multisets.extend(card_multisets(rest, k));
+    multisets
+}
+
+/// All unordered pairs of cards, e.g. the possible hole cards out of the rest of the deck.
+pub fn card_pairs(cards: &[Card]) -> Vec<(Card, Card)> {
+    combinations(cards, 2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect()
+}
+
+/// How many ranks appear exactly twice among any number of cards, e.g. 3 for a seven card
+/// hand holding three pairs, of which only the two highest count in a five card hand.
+pub fn count_pairs(cards: &[Card]) -> usize {
+    // skip the LowAce slot, otherwise a pair of aces is counted twice
+    PokerHand::get_rank_map(cards)[2..]
+        .iter()
+        .filter(|&&n| n == 2)
+        .count()
+}
+
+/// Returns the strongest five card hand which can be made out of the given cards.
+///
+/// Panics if fewer than five cards are given.
+pub fn best_of(cards: &[Card]) -> OwnedPokerHand {
+    assert!(
+        cards.len() >= 5,
+        "At least 5 cards are needed to make a hand"
+    );
+    combinations(cards, 5)
+        .map(|combination| {
+            PokerHand::from_cards(combination.try_into().expect("Expected exactly 5 cards"))
+        })
+        .max_by(|a, b| a.compare_strength(b))
+        .unwrap()
+}
+
+/// The category and the `strength_key` of the best hand out of seven cards, enough to
+/// store and compare the hand without keeping it around.
+pub fn evaluate_seven(cards: &[Card; 7]) -> (PokerHandRank, u32) {
+    let best = best_of(cards);
+    (best.rank(), best.strength_key())
+}
+
+/// Returns the strongest hand that any two hole cards from the rest of the deck can make
+/// with the given board.
+///
+/// Panics if the board has fewer than three cards.
+pub fn the_nuts(board: &[Card]) -> OwnedPokerHand {
+    possible_hands(board)
+        .max_by(|a, b| a.compare_strength(b))
+        .unwrap()
+}
+
+/// Returns the `n` strongest hands that any two hole cards from the rest of the deck can
+/// make with the given board, strongest first. Hands of equal strength are only listed once.
+///
+/// Panics if the board has fewer than three cards.
+pub fn top_n_hands(board: &[Card], n: usize) -> Vec<OwnedPokerHand> {
+    let mut hands: Vec<OwnedPokerHand> = possible_hands(board).collect();
+    hands.sort_by(|a, b| b.compare_strength(a));
+    hands.dedup_by(|a, b| a.compare_strength(b) == Ordering::Equal);
+    hands.truncate(n);
+    hands
+}
+
+/// Whether holding `my_cards` blocks the nuts, that is, every pair of hole cards making the
+/// nuts with the board holds one of them, e.g. the Ace of the suite for the nut flush.
+///
+/// Panics if the board has fewer than three cards.
+pub fn blocks_nuts(my_cards: &[Card], board: &[Card]) -> bool {
+    let nuts = the_nuts(board);
+    card_pairs(&unseen_cards(board))
+        .into_iter()
+        .filter(|(first, second)| {
+            let cards: Vec<Card> = board.iter().chain([first, second]).copied().collect();
+            best_of(&cards).compare_strength(&nuts) == Ordering::Equal
+        })
+        .all(|(first, second)| {
+            my_cards
+                .iter()
+                .any(|card| card.is_same_card(&first) || card.is_same_card(&second))
+        })
+}
+
+/// The unseen cards which, once added to the board, give `my_cards` the nuts.
+///
+/// Panics if the board has fewer than two cards.
+pub fn outs_to_nuts(my_cards: &[Card], board: &[Card]) -> Vec<Card> {
+    let known: Vec<Card> = my_cards.iter().chain(board).copied().collect();
+    unseen_cards(&known)
+        .into_iter()
+        .filter(|&out| {
+            let next_board: Vec<Card> = board.iter().copied().chain([out]).collect();
+            let cards: Vec<Card> = my_cards.iter().chain(&next_board).copied().collect();
+            best_of(&cards).compare_strength(&the_nuts(&next_board)) == Ordering::Equal
+        })
+        .collect()
+}
+
+/// The unseen cards which, once added to the board, lift the best hand of `my_cards` with
+/// it into a stronger category.
+///
+/// Panics if there are fewer than five cards between `my_cards` and the board.
+pub fn improvement_outs(my_cards: &[Card], board: &[Card]) -> Vec<Card> {
+    let known: Vec<Card> = my_cards.iter().chain(board).copied().collect();
+    let current = best_of(&known).rank();
+    unseen_cards(&known)
+        .into_iter()
+        .filter(|&out| {
+            let cards: Vec<Card> = known.iter().copied().chain([out]).collect();
+            best_of(&cards).rank() > current
+        })
+        .collect()
+}
+
+/// Blends how strong the best hand of `cards` with the board already is and how likely it
+/// is to improve: `p + (1 - p) * outs / unseen`, where `p` is the `percentile` of the made
+/// hand, `outs` counts the `improvement_outs` and `unseen` the cards left in the deck.
+///
+/// Panics if there are fewer than five cards between `cards` and the board.
+pub fn playability(cards: &[Card], board: &[Card]) -> f64 {
+    let known: Vec<Card> = cards.iter().chain(board).copied().collect();
+    let made = best_of(&known).percentile();
+    let outs = improvement_outs(cards, board).len();
+    let unseen = unseen_cards(&known).len();
+    made + (1.0 - made) * outs as f64 / unseen as f64
+}
+
+// The best hand of each possible pair of hole cards with the board.
+fn possible_hands(board: &[Card]) -> impl Iterator<Item = OwnedPokerHand> + '_ {
+    card_pairs(&unseen_cards(board))
+        .into_iter()
+        .map(move |(first, second)| {
+            let cards: Vec<Card> = board.iter().chain([&first, &second]).copied().collect();
+            best_of(&cards)
+        })
+}
+
+/// Deals `samples` random opponent hands out of the rest of the deck and returns the
+/// fraction of them which `hand` beats or ties.
+///
+/// Panics if `samples` is zero, as there is no fraction of no hands.
+#[cfg(feature = "rand")]
+pub fn win_rate_vs_random(hand: &[Card; 5], samples: usize, rng: &mut impl Rng) -> f64 {
+    assert!(samples > 0, "At least one sample is needed");
+    let deck = unseen_cards(hand);
+    let hand = PokerHand::from_cards(*hand);
+    let wins = (0..samples)
+        .filter(|_| {
+            let opponent: Vec<Card> = deck.choose_multiple(rng, 5).copied().collect();
+            let opponent =
+                PokerHand::from_cards(opponent.try_into().expect("Expected exactly 5 cards"));
+            hand.compare_strength(&opponent) != Ordering::Less
+        })
+        .count();
+    wins as f64 / samples as f64
+}
+
+/// Runs the board out `samples` times with random cards from the rest of the deck, and
+/// returns the fractions of the run-outs where hand `a` wins, ties and loses against `b`.
+///
+/// Panics if the board holds more than five cards, or too few to make a hand with either.
+#[cfg(feature = "rand")]
+pub fn equity(
+    a: &[Card],
+    b: &[Card],
+    board: &[Card],
+    samples: usize,
+    rng: &mut impl Rng,
+) -> (f64, f64, f64) {
+    let known: Vec<Card> = a.iter().chain(b).chain(board).copied().collect();
+    let deck = unseen_cards(&known);
+    let mut outcomes = [0; 3];
+    for _ in 0..samples {
+        let runout: Vec<Card> = board
+            .iter()
+            .chain(deck.choose_multiple(rng, 5 - board.len()))
+            .copied()
+            .collect();
+        let best = |hole: &[Card]| {
+            let cards: Vec<Card> = hole.iter().chain(&runout).copied().collect();
+            best_of(&cards)
+        };
+        match best(a).compare_strength(&best(b)) {
+            Ordering::Greater => outcomes[0] += 1,
+            Ordering::Equal => outcomes[1] += 1,
+            Ordering::Less => outcomes[2] += 1,
+        }
+    }
+    let [win, tie, lose] = outcomes.map(|count| count as f64 / samples as f64);
+    (win, tie, lose)
+}
+
+/// The fraction of `samples` random run-outs of the board where hands `a` and `b` split the
+/// pot, the tie part of their `equity`.
+#[cfg(feature = "rand")]
+pub fn chop_probability(
+    a: &[Card],
+    b: &[Card],
+    board: &[Card],
+    samples: usize,
+    rng: &mut impl Rng,
+) -> f64 {
+    equity(a, b, board, samples, rng).1
+}
+
+/// Deals random hole cards out of ranges `a` and `b` `samples` times, runs each deal out once
+/// with the board, and returns the fractions of the deals where `a` wins, ties and loses.
+/// Deals only use hole cards which don't collide with each other or the board, so returns
+/// `None` when no deal could be made at all, e.g. with no samples or an empty range.
+///
+/// Panics if the board holds more than five cards.
+#[cfg(feature = "rand")]
+pub fn range_vs_range(
+    a: &[(CardRank, CardRank, bool)],
+    b: &[(CardRank, CardRank, bool)],
+    board: &[Card],
+    samples: usize,
+    rng: &mut impl Rng,
+) -> Option<(f64, f64, f64)> {
+    let unseen = unseen_cards(board);
+    let expand = |range: &[(CardRank, CardRank, bool)]| -> Vec<(Card, Card)> {
+        range
+            .iter()
+            .flat_map(|&class| class_combos(&unseen, class))
+            .collect()
+    };
+    let (a_combos, b_combos) = (expand(a), expand(b));
+    let mut totals = (0.0, 0.0, 0.0);
+    let mut deals = 0;
+    for _ in 0..samples {
+        let Some(&(a1, a2)) = a_combos.choose(rng) else {
+            continue;
+        };
+        let b_left: Vec<&(Card, Card)> = b_combos
+            .iter()
+            .filter(|(b1, b2)| {
+                ![b1, b2]
+                    .iter()
+                    .any(|b| b.is_same_card(&a1) || b.is_same_card(&a2))
+            })
+            .collect();
+        let Some(&&(b1, b2)) = b_left.choose(rng) else {
+            continue;
+        };
+        let (win, tie, lose) = equity(&[a1, a2], &[b1, b2], board, 1, rng);
+        totals = (totals.0 + win, totals.1 + tie, totals.2 + lose);
+        deals += 1;
+    }
+    if deals == 0 {
+        return None;
+    }
+    let deals = deals as f64;
+    Some((totals.0 / deals, totals.1 / deals, totals.2 / deals))
+}
+
+/// Which hand is ahead in an all-in race, see `race_favorite`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RaceResult {
+    /// Hand `a` wins more often, by this fraction of the run-outs.
+    AFavored(f64),
+    /// Hand `b` wins more often, by this fraction of the run-outs.
+    BFavored(f64),
+    /// Both hands win exactly as often.
+    CoinFlip,
+}
+
+/// Finds which hand is the favorite in a race, and by how much more often it wins, by
+/// sampling the `equity` of the hands.
+#[cfg(feature = "rand")]
+pub fn race_favorite(
+    a: &[Card],
+    b: &[Card],
+    board: &[Card],
+    rng: &mut impl Rng,
+    samples: usize,
+) -> RaceResult {
+    let (win, _, lose) = equity(a, b, board, samples, rng);
+    match win.partial_cmp(&lose) {
+        Some(Ordering::Greater) => RaceResult::AFavored(win - lose),
+        Some(Ordering::Less) => RaceResult::BFavored(lose - win),
+        _ => RaceResult::CoinFlip,
+    }
+}
+
+/// The expected value of calling `to_call` into a `pot` which already holds the opponent's bet,
+/// given the `equity` (the chance to win) of the hand: `equity * pot - (1 - equity) * to_call`.
+pub fn showdown_ev(equity: f64, pot: f64, to_call: f64) -> f64 {
+    equity * pot - (1.0 - equity) * to_call
+}
+
+/// The minimum defense frequency against a `bet` into a `pot`: `pot / (pot + bet)`, how often
+/// a hand must continue so that a bluff of that size doesn't profit automatically.
+pub fn min_defense_frequency(bet: f64, pot: f64) -> f64 {
+    pot / (pot + bet)
+}
+
+/// Completes `partial` up to five cards with random cards from the rest of the deck
+/// `samples` times, and returns the fraction of hands landing in each category, indexed
+/// by the category number minus one.
+///
+/// Panics if more than five cards are given, or if `samples` is zero.
+#[cfg(feature = "rand")]
+pub fn category_distribution(partial: &[Card], samples: usize, rng: &mut impl Rng) -> [f64; 10] {
+    assert!(partial.len() <= 5, "A hand holds at most 5 cards");
+    assert!(samples > 0, "At least one sample is needed");
+    let deck = unseen_cards(partial);
+    let mut counts = [0; 10];
+    for _ in 0..samples {
+        let cards: Vec<Card> = partial
+            .iter()
+            .chain(deck.choose_multiple(rng, 5 - partial.len()))
+            .copied()
+            .collect();
+        let rank = PokerHand::get_rank(cards.try_into().expect("Expected exactly 5 cards"));
+        counts[rank.as_number() as usize - 1] += 1;
+    }
+    counts.map(|count| count as f64 / samples as f64)
+}
+
+/// Deals `num_players` five card hands out of one shuffled deck and classifies each of them,
+/// or returns `None` if a deck doesn't hold enough cards for all the players.
+#[cfg(feature = "rand")]
+pub fn deal_and_evaluate(num_players: usize, rng: &mut impl Rng) -> Option<Vec<OwnedPokerHand>> {
+    let mut deck = Deck::new();
+    if num_players.checked_mul(5)? > deck.len() {
+        return None;
+    }
+    deck.shuffle(rng);
+    (0..num_players)
+        .map(|_| {
+            let cards = deck.deal(5)?;
+            Some(PokerHand::from_cards(
+                cards.try_into().expect("Expected exactly 5 cards"),
+            ))
+        })
+        .collect()
+}
+
+/// The runner-runner draws on the flop, see `backdoor_draws`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackdoorInfo {
+    /// Three cards of one suite, so a suited turn and river make a flush.
+    pub flush: bool,
+    /// Three ranks within a straight, so the right turn and river make it.
+    pub straight: bool,
+}
+
+/// Finds the backdoor flush and straight draws of the hole cards with the flop.
+pub fn backdoor_draws(hole: &[Card; 2], flop: &[Card; 3]) -> BackdoorInfo {
+    let cards: Vec<Card> = hole.iter().chain(flop).copied().collect();
+    BackdoorInfo {
+        flush: PokerHand::get_suite_map(&cards).contains(&3),
+        straight: possible_straights(&distinct_ranks(&cards)) > 0,
+    }
+}
+
+/// Which of two hands holds a freeroll, see `is_freeroll`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreerollDirection {
+    A,
+    B,
+}
+
+/// Whether one of two hands tying on the current board holds a freeroll, that is, some of
+/// the cards still to come make it win while none make it lose.
+///
+/// Panics if the board holds more than five cards, or too few to make a hand with either.
+pub fn is_freeroll(a: &[Card], b: &[Card], board: &[Card]) -> Option<FreerollDirection> {
+    let best = |hole: &[Card], board: &[Card]| {
+        let cards: Vec<Card> = hole.iter().chain(board).copied().collect();
+        best_of(&cards)
+    };
+    if best(a, board).compare_strength(&best(b, board)) != Ordering::Equal {
+        return None;
+    }
+
+    let known: Vec<Card> = a.iter().chain(b).chain(board).copied().collect();
+    let (mut a_wins, mut b_wins) = (false, false);
+    for runout in combinations(&unseen_cards(&known), 5 - board.len()) {
+        let board: Vec<Card> = board.iter().chain(&runout).copied().collect();
+        match best(a, &board).compare_strength(&best(b, &board)) {
+            Ordering::Greater => a_wins = true,
+            Ordering::Less => b_wins = true,
+            Ordering::Equal => {}
+        }
+    }
+    match (a_wins, b_wins) {
+        (true, false) => Some(FreerollDirection::A),
+        (false, true) => Some(FreerollDirection::B),
+        _ => None,
+    }
+}
+
+/// How paired a board is, which tells how likely full houses and better are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairRisk {
+    None,
+    Paired,
+    DoublePaired,
+    /// Three or more cards of one rank.
+    Tripled,
+}
+
+/// Classifies how paired the board is, from its most repeated ranks.
+pub fn board_pair_risk(board: &[Card]) -> PairRisk {
+    // skip the LowAce slot, otherwise a pair of aces is counted twice
+    let ranks = &PokerHand::get_rank_map(board)[2..];
+    if ranks.iter().any(|&n| n >= 3) {
+        return PairRisk::Tripled;
+    }
+    match ranks.iter().filter(|&&n| n == 2).count() {
+        0 => PairRisk::None,
+        1 => PairRisk::Paired,
+        _ => PairRisk::DoublePaired,
+    }
+}
+
+/// How the suites of the board are spread, see `board_texture`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardTexture {
+    /// No two cards share a suite.
+    Rainbow,
+    /// Exactly two cards share the most common suite, so there may be a flush draw.
+    TwoTone,
+    /// Three or more cards share a suite, but not every card does, so a flush is possible.
+    FlushPossible,
+    /// Every card, at least three of them, shares one suite.
+    Monotone,
+}
+
+/// Classifies the board by its most common suite.
+pub fn board_texture(board: &[Card]) -> BoardTexture {
+    match PokerHand::get_suite_map(board).iter().max() {
+        Some(&n) if n >= 3 && n == board.len() => BoardTexture::Monotone,
+        Some(&n) if n >= 3 => BoardTexture::FlushPossible,
+        Some(2) => BoardTexture::TwoTone,
+        _ => BoardTexture::Rainbow,
+    }
+}
+
+// Whether three or more cards of the board share a suite, so two suited hole cards make a flush.
+fn flush_possible(board: &[Card]) -> bool {
+    PokerHand::get_suite_map(board).iter().any(|&n| n >= 3)
+}
+
+/// Whether the board is coordinated enough to play cautiously on: a flush is possible, it is
+/// paired, or it holds four ranks within a single straight.
+pub fn should_slow_down(board: &[Card]) -> bool {
+    flush_possible(board)
+        || board_pair_risk(board) != PairRisk::None
+        || straight_windows(&distinct_ranks(board), 4) > 0
+}
+
+/// The outcome of a showdown for one player, see `result_against`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShowdownResult {
+    Win,
+    /// Split with this many opponents holding a hand as strong.
+    Tie(usize),
+    Lose,
+}
+
+/// Shows `my` hand down against every opponent: it wins when it beats them all, ties when
+/// no opponent beats it but some are as strong, and loses otherwise.
+pub fn result_against(my: &[Card; 5], opponents: &[[Card; 5]]) -> ShowdownResult {
+    let my = PokerHand::from_cards(*my);
+    let mut ties = 0;
+    for opponent in opponents {
+        match my.compare_strength(&PokerHand::from_cards(*opponent)) {
+            Ordering::Less => return ShowdownResult::Lose,
+            Ordering::Equal => ties += 1,
+            Ordering::Greater => {}
+        }
+    }
+    match ties {
+        0 => ShowdownResult::Win,
+        n => ShowdownResult::Tie(n),
+    }
+}
+
+/// Whether `losing` is a bad beat: a hand of at least the `min_losing` category which still
+/// lost to `winning`, as bad-beat jackpots require.
+pub fn qualifies_bad_beat(
+    losing: &PokerHand,
+    winning: &PokerHand,
+    min_losing: PokerHandRank,
+) -> bool {
+    losing.rank() >= min_losing && losing.compare_strength(winning) == Ordering::Less
+}
+
+/// Whether `my_cards` can no longer win against `opponent_best`. With the whole board dealt
+/// there are no cards to come, so this is whether my best hand loses to it.
+pub fn is_drawing_dead(my_cards: &[Card], board: &[Card; 5], opponent_best: &PokerHand) -> bool {
+    let cards: Vec<Card> = my_cards.iter().chain(board).copied().collect();
+    best_of(&cards).compare_strength(opponent_best) == Ordering::Less
+}
+
+/// Whether the best hand out of the hole cards and the board is just the board itself,
+/// that is, neither hole card improves on the community cards.
+pub fn plays_the_board(hole: &[Card; 2], board: &[Card; 5]) -> bool {
+    let cards: Vec<Card> = hole.iter().chain(board).copied().collect();
+    best_of(board).compare_strength(&best_of(&cards)) == Ordering::Equal
+}
+
+/// Finds four suited cards lying within a single five rank straight window, that is, an
+/// open-ended or gutshot straight flush draw. Returns the suite and the ascending ranks of
+/// the four cards, preferring the highest window. Aces count both high and low, but are
+/// returned as `CardRank::Ace`, e.g. A 2 3 4 gives 2 3 4 A. A straight flush already made
+/// is not a draw, so it gives `None`.
+pub fn straight_flush_draw(cards: &[Card]) -> Option<(CardSuite, Vec<CardRank>)> {
+    let made = CardSuite::all().any(|suite| {
+        let suited: Vec<Card> = cards
+            .iter()
+            .filter(|card| card.suite == suite)
+            .copied()
+            .collect();
+        has_rank_run(rank_bitset(&suited), 5)
+    });
+    if made {
+        return None;
+    }
+    CardSuite::all().find_map(|suite| {
+        let mut held = [false; 15];
+        for card in cards.iter().filter(|card| card.suite == suite) {
+            let rank = card.rank.ace_high().as_number() as usize;
+            held[rank] = true;
+            if rank == 14 {
+                held[1] = true; // Ace can also be considered as 1
+            }
+        }
+        (1..=10).rev().find_map(|low| {
+            let mut ranks: Vec<CardRank> = (low..low + 5)
+                .filter(|&rank| held[rank])
+                .map(|rank| CardRank::from_number(rank as u8).ace_high())
+                .collect();
+            ranks.sort();
+            (ranks.len() == 4).then_some((suite, ranks))
+        })
+    })
+}
+
+/// The exact cards completing a straight flush from four suited cards within a five rank
+/// window, in ascending order. Aces count both high and low.
+pub fn straight_flush_outs(cards: &[Card]) -> Vec<Card> {
+    CardSuite::all()
+        .flat_map(|suite| {
+            let suited: Vec<Card> = cards
+                .iter()
+                .filter(|card| card.suite == suite)
+                .copied()
+                .collect();
+            let rank_bits = with_low_ace(rank_bitset(&suited));
+            let window = 0b11111;
+            let mut ranks: Vec<CardRank> = (1..=10)
+                .filter(|low| (rank_bits >> low & window).count_ones() == 4)
+                .filter_map(|low| (low..low + 5).find(|rank| rank_bits & 1 << rank == 0))
+                .map(|rank| CardRank::from_number(rank).ace_high())
+                .collect();
+            ranks.sort();
+            ranks.dedup();
+            ranks.into_iter().map(move |rank| Card { rank, suite })
+        })
+        .collect()
+}
+
+/// How a pair made with the hole cards relates to the ranks on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairRelation {
+    /// A pocket pair above every board card.
+    Overpair,
+    /// A hole card pairing the highest board card.
+    TopPair,
+    /// A hole card pairing a lower board card, or a pocket pair between the board cards.
+    MiddlePair,
+    /// A pocket pair below every board card.
+    UnderPair,
+}
+
+/// Classifies the pair made by the hole cards against the board, if they make exactly one
+/// pair. A pocket pair matching a board card makes three of a kind, so it is not a pair.
+pub fn pair_relation(hole: &[Card; 2], board: &[Card]) -> Option<PairRelation> {
+    let board_ranks: Vec<CardRank> = board.iter().map(|card| card.rank.ace_high()).collect();
+    let highest = *board_ranks.iter().max()?;
+    let lowest = *board_ranks.iter().min()?;
+    let (first, second) = (hole[0].rank.ace_high(), hole[1].rank.ace_high());
+
+    if first == second {
+        return match first {
+            rank if board_ranks.contains(&rank) => None,
+            rank if rank > highest => Some(PairRelation::Overpair),
+            rank if rank < lowest => Some(PairRelation::UnderPair),
+            _ => Some(PairRelation::MiddlePair),
+        };
+    }
+
+    [first, second]
+        .into_iter()
+        .filter(|rank| board_ranks.contains(rank))
+        .max()
+        .map(|rank| {
+            if rank == highest {
+                PairRelation::TopPair
+            } else {
+                PairRelation::MiddlePair
             }
-            PokerHandRank::FourOfAKind => {
-                pairs = None;
-                triplet = None;
-                quadruplet = PokerHand::get_quadruplet(cards);
-                sequence = None;
+        })
+}
+
+/// The reasons why a starting hand range can't be parsed. Each variant holds the offending
+/// part of the range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeParseError {
+    /// A starting hand is not made of two ranks followed by an optional `s`/`o` and `+`.
+    InvalidHand(String),
+    InvalidRank(String),
+    /// A pair can't be suited.
+    SuitedPair(String),
+}
+
+impl fmt::Display for RangeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeParseError::InvalidHand(s) => write!(f, "Invalid starting hand: \"{}\"", s),
+            RangeParseError::InvalidRank(s) => {
+                write!(f, "Invalid rank in starting hand: \"{}\"", s)
             }
-            PokerHandRank::StraightFlush => {
-                pairs = None;
-                triplet = None;
-                quadruplet = None;
-                sequence = PokerHand::get_sequence(cards);
+            RangeParseError::SuitedPair(s) => write!(f, "A pair can't be suited: \"{}\"", s),
+        }
+    }
+}
+
+impl Error for RangeParseError {}
+
+/// Writes cards the way solvers and hand histories do, e.g. "AhKh": each rank as a single
+/// character out of "23456789TJQKA" followed by the suite in lowercase, highest card first
+/// and, within a rank, Spades before Hearts, Diamonds and Clubs.
+pub fn hole_cards_label(cards: &[Card]) -> String {
+    let mut cards = cards.to_vec();
+    cards.sort_by(|a, b| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+    cards
+        .iter()
+        .map(|card| {
+            let rank = match card.rank.ace_high() {
+                CardRank::Ten => "T".to_string(),
+                rank => rank.to_string(),
+            };
+            format!("{}{}", rank, card.suite.to_string().to_lowercase())
+        })
+        .collect()
+}
+
+/// Expands a range of starting hands, e.g. "AKs, QQ+, T9o", into `(high, low, suited)`
+/// classes, in the order they are given and without repeats.
+///
+/// A pair followed by `+` also takes every higher pair, and a non-pair followed by `+` takes
+/// every higher kicker below the high card, e.g. "A9s+" is A9s up to AKs. A non-pair without
+/// `s` or `o` takes both the suited and the offsuit class. Every rank is a single character,
+/// so tens must be written as `T`: "A10o" is an `InvalidRank`.
+pub fn parse_range(s: &str) -> Result<Vec<(CardRank, CardRank, bool)>, RangeParseError> {
+    let mut classes: Vec<(CardRank, CardRank, bool)> = vec![];
+    for hand in s.split(',').map(str::trim) {
+        let chars: Vec<char> = hand.chars().collect();
+        let (rank_chars, mut modifiers) = match chars.len() {
+            2..=4 => chars.split_at(2),
+            _ => return Err(RangeParseError::InvalidHand(hand.to_string())),
+        };
+        let parse_rank = |c: char| CardRank::parse(&c.to_string());
+        let (Some(first), Some(second)) = (parse_rank(rank_chars[0]), parse_rank(rank_chars[1]))
+        else {
+            return Err(RangeParseError::InvalidRank(hand.to_string()));
+        };
+        let (high, low) = (first.max(second), first.min(second));
+
+        let suitedness = match modifiers.first() {
+            Some('s') => {
+                modifiers = &modifiers[1..];
+                vec![true]
             }
-            PokerHandRank::RoyalFlush => {
-                pairs = None;
-                triplet = None;
-                quadruplet = None;
-                sequence = PokerHand::get_sequence(cards);
+            Some('o') => {
+                modifiers = &modifiers[1..];
+                vec![false]
             }
+            _ if high == low => vec![false],
+            _ => vec![true, false],
+        };
+        let plus = match modifiers {
+            [] => false,
+            ['+'] => true,
+            _ => return Err(RangeParseError::InvalidHand(hand.to_string())),
+        };
+        if high == low && suitedness == [true] {
+            return Err(RangeParseError::SuitedPair(hand.to_string()));
         }
 
-        PokerHand {
-            raw,
-            rank,
-            cards,
-            pairs,
-            triplet,
-            quadruplet,
-            sequence,
+        let ranks: Vec<(CardRank, CardRank)> = match (plus, high == low) {
+            (false, _) => vec![(high, low)],
+            (true, true) => CardRank::all()
+                .filter(|rank| rank.as_number() >= low.as_number())
+                .map(|rank| (rank, rank))
+                .collect(),
+            (true, false) => CardRank::all()
+                .filter(|rank| (low.as_number()..high.as_number()).contains(&rank.as_number()))
+                .map(|rank| (high, rank))
+                .collect(),
+        };
+        for (high, low) in ranks {
+            for &suited in &suitedness {
+                if !classes.contains(&(high, low, suited)) {
+                    classes.push((high, low, suited));
+                }
+            }
         }
     }
+    Ok(classes)
+}
+
+// The concrete hole cards of a `(high, low, suited)` starting hand class out of `cards`.
+fn class_combos(
+    cards: &[Card],
+    (high, low, suited): (CardRank, CardRank, bool),
+) -> Vec<(Card, Card)> {
+    card_pairs(cards)
+        .into_iter()
+        .filter(|(first, second)| {
+            let ranks = (first.rank.ace_high(), second.rank.ace_high());
+            (ranks == (high, low) || ranks == (low, high))
+                && (first.suite == second.suite) == suited
+        })
+        .collect()
+}
+
+/// Whether starting hand `a` dominates `b`: they share a rank and the other rank of `a` is
+/// higher, e.g. Ace King dominates Ace Queen.
+pub fn dominates(a: (CardRank, CardRank), b: (CardRank, CardRank)) -> bool {
+    let a = (a.0.ace_high(), a.1.ace_high());
+    let b = (b.0.ace_high(), b.1.ace_high());
+    [(a.0, a.1), (a.1, a.0)]
+        .into_iter()
+        .any(|(shared, kicker)| {
+            [(b.0, b.1), (b.1, b.0)]
+                .into_iter()
+                .any(|(other_shared, other_kicker)| shared == other_shared && kicker > other_kicker)
+        })
+}
+
+/// Counts the concrete hole cards out of `range` which hold none of the `dead` cards. Before
+/// blockers, a pocket pair has 6 combos, a suited hand 4 and an offsuit hand 12.
+pub fn combos_remaining(range: &[(CardRank, CardRank, bool)], dead: &[Card]) -> usize {
+    let unseen = unseen_cards(dead);
+    range
+        .iter()
+        .map(|&class| class_combos(&unseen, class).len())
+        .sum()
+}
+
+/// Counts the concrete hole cards out of `range` whose best hand with the `dead` cards as
+/// the board beats `target`. Hole cards can't be any of the target's or the dead cards.
+///
+/// Panics if there are fewer than three dead cards.
+pub fn hands_beating_range(
+    target: &PokerHand,
+    range: &[(CardRank, CardRank, bool)],
+    dead: &[Card],
+) -> u64 {
+    let known: Vec<Card> = target.cards.iter().chain(dead).copied().collect();
+    let unseen = unseen_cards(&known);
+    range
+        .iter()
+        .flat_map(|&class| class_combos(&unseen, class))
+        .filter(|(first, second)| {
+            let cards: Vec<Card> = dead.iter().chain([first, second]).copied().collect();
+            best_of(&cards).compare_strength(target) == Ordering::Greater
+        })
+        .count() as u64
+}
+
+/// Whether the best hand of `my` cards with the board beats or ties every concrete hand in
+/// `villain_range`, that is, it is the nuts against what the villain can actually hold.
+///
+/// Panics if the board has fewer than three cards.
+pub fn is_effective_nuts(
+    my: &[Card],
+    board: &[Card],
+    villain_range: &[(CardRank, CardRank, bool)],
+) -> bool {
+    let known: Vec<Card> = my.iter().chain(board).copied().collect();
+    let mine = best_of(&known);
+    let unseen = unseen_cards(&known);
+    villain_range
+        .iter()
+        .flat_map(|&class| class_combos(&unseen, class))
+        .all(|(first, second)| {
+            let cards: Vec<Card> = board.iter().chain([&first, &second]).copied().collect();
+            best_of(&cards).compare_strength(&mine) != Ordering::Greater
+        })
 }
 
 pub struct PokerHandVec<'a>(Vec<PokerHand<'a>>);
 
+impl<'a> From<Vec<PokerHand<'a>>> for PokerHandVec<'a> {
+    fn from(hands: Vec<PokerHand<'a>>) -> Self {
+        PokerHandVec(hands)
+    }
+}
+
+/// With a width, e.g. `{:3}`, renders one hand per line with every card padded to that
+/// width, so the cards line up in columns.
 impl<'a> fmt::Display for PokerHandVec<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(width) = f.width() {
+            let rows: Vec<String> = self
+                .0
+                .iter()
+                .map(|hand| {
+                    hand.cards
+                        .iter()
+                        .map(|card| card.to_padded_string(width))
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect();
+            return write!(f, "{}", rows.join("\n"));
+        }
         write!(
             f,
             "[{}]",
@@ -646,25 +2693,134 @@ impl<'a> fmt::Display for PokerHandVec<'a> {
     }
 }
 
-fn untie(hands: Vec<PokerHand>, rank: PokerHandRank) -> Vec<PokerHand> {
+/// The criterion compared at one step of breaking a tie between hands of the same category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreakCriterion {
+    Sequence,
+    Quadruplet,
+    Triplet,
+    Pair,
+    SecondPair,
+    /// The n-th highest card outside of the made combination, starting at 1.
+    Kicker(usize),
+}
+
+impl fmt::Display for TieBreakCriterion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TieBreakCriterion::Sequence => write!(f, "sequence rank"),
+            TieBreakCriterion::Quadruplet => write!(f, "quadruplet rank"),
+            TieBreakCriterion::Triplet => write!(f, "triplet rank"),
+            TieBreakCriterion::Pair => write!(f, "pair rank"),
+            TieBreakCriterion::SecondPair => write!(f, "second pair rank"),
+            TieBreakCriterion::Kicker(n) => write!(f, "kicker {}", n),
+        }
+    }
+}
+
+/// One decision taken while breaking a tie: which rank each remaining hand had for
+/// the compared criterion, and which hands were eliminated because of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TieBreakStep<'a> {
+    criterion: TieBreakCriterion,
+    compared: Vec<(&'a str, CardRank)>,
+    eliminated: Vec<&'a str>,
+}
+
+impl<'a> TieBreakStep<'a> {
+    fn new(criterion: TieBreakCriterion, compared: Vec<(&'a str, CardRank)>) -> Self {
+        let highest = compared.iter().map(|&(_, rank)| rank).max();
+        let eliminated = compared
+            .iter()
+            .filter(|&&(_, rank)| Some(rank) != highest)
+            .map(|&(raw, _)| raw)
+            .collect();
+        TieBreakStep {
+            criterion,
+            compared,
+            eliminated,
+        }
+    }
+
+    pub fn criterion(&self) -> TieBreakCriterion {
+        self.criterion
+    }
+
+    pub fn compared(&self) -> &[(&'a str, CardRank)] {
+        &self.compared
+    }
+
+    pub fn eliminated(&self) -> &[&'a str] {
+        &self.eliminated
+    }
+}
+
+impl<'a> fmt::Display for TieBreakStep<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranks = self
+            .compared
+            .iter()
+            .map(|(_, rank)| rank.to_string())
+            .collect::<Vec<String>>()
+            .join(" vs ");
+        if self.eliminated.is_empty() {
+            write!(f, "compared {}: {} -> tie, continue", self.criterion, ranks)
+        } else {
+            let eliminated = self
+                .eliminated
+                .iter()
+                .map(|raw| format!("\"{}\"", raw))
+                .collect::<Vec<String>>()
+                .join(", ");
+            write!(
+                f,
+                "compared {}: {} -> eliminated {}",
+                self.criterion, ranks, eliminated
+            )
+        }
+    }
+}
+
+// Records a tie-break step comparing the given hands by `value`, if there is still a tie to break.
+fn record_step<'a>(
+    steps: &mut Vec<TieBreakStep<'a>>,
+    criterion: TieBreakCriterion,
+    hands: &[PokerHand<'a>],
+    value: impl Fn(&PokerHand<'a>) -> Option<CardRank>,
+) {
+    if hands.len() <= 1 {
+        return;
+    }
+    let compared = hands
+        .iter()
+        .filter_map(|hand| value(hand).map(|rank| (hand.raw.unwrap_or_default(), rank)))
+        .collect();
+    steps.push(TieBreakStep::new(criterion, compared));
+}
+
+fn untie<'a>(
+    hands: Vec<PokerHand<'a>>,
+    rank: PokerHandRank,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
     let hands: Vec<PokerHand> = hands.into_iter().filter(|hand| hand.rank == rank).collect();
 
     match rank {
-        PokerHandRank::HighCard => untie_highest(hands, None),
-        PokerHandRank::Flush => untie_highest(hands, None),
-        PokerHandRank::ThreeOfAKind => untie_three(hands),
-        PokerHandRank::FourOfAKind => untie_four(hands),
-        PokerHandRank::OnePair => untie_one_pair(hands),
-        PokerHandRank::TwoPairs => untie_two_pairs(hands),
-        PokerHandRank::Straight => untie_straight(hands),
-        PokerHandRank::StraightFlush => untie_straight(hands),
-        PokerHandRank::FullHouse => untie_full_house(hands),
+        PokerHandRank::HighCard => untie_highest(hands, None, steps),
+        PokerHandRank::Flush => untie_highest(hands, None, steps),
+        PokerHandRank::ThreeOfAKind => untie_three(hands, steps),
+        PokerHandRank::FourOfAKind => untie_four(hands, steps),
+        PokerHandRank::OnePair => untie_one_pair(hands, steps),
+        PokerHandRank::TwoPairs => untie_two_pairs(hands, steps),
+        PokerHandRank::Straight => untie_straight(hands, steps),
+        PokerHandRank::StraightFlush => untie_straight(hands, steps),
+        PokerHandRank::FullHouse => untie_full_house(hands, steps),
         _ => hands,
     }
 }
 
 fn get_cards(hands: Vec<PokerHand>) -> Vec<Card> {
-    hands.iter().flat_map(|hand| hand.cards.clone()).collect()
+    hands.iter().flat_map(|hand| hand.cards).collect()
 }
 
 // This function takes a vector of PokerHand objects and a vector of CardRank objects.
@@ -706,8 +2862,23 @@ impl Default for UntieHighestConfig {
     }
 }
 
-fn untie_highest(hands: Vec<PokerHand>, config: Option<UntieHighestConfig>) -> Vec<PokerHand> {
+fn untie_highest<'a>(
+    hands: Vec<PokerHand<'a>>,
+    config: Option<UntieHighestConfig>,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
     let config = config.unwrap_or_default();
+    // the highest card of a hand which isn't one of the tied ranks
+    let kicker = |tied_ranks: &[CardRank]| {
+        let tied_ranks = tied_ranks.to_vec();
+        move |hand: &PokerHand| {
+            hand.cards
+                .iter()
+                .map(|card| card.rank)
+                .filter(|rank| !tied_ranks.contains(rank))
+                .max()
+        }
+    };
     // tied ranks
     let skip_ranks = config.ranks.clone();
     let mut tied_ranks = config.ranks.clone();
@@ -720,22 +2891,34 @@ fn untie_highest(hands: Vec<PokerHand>, config: Option<UntieHighestConfig>) -> V
     all_cards = filter_cards(all_cards, skip_ranks);
 
     // find the highest card
+    record_step(
+        steps,
+        TieBreakCriterion::Kicker(1),
+        &hands,
+        kicker(&tied_ranks),
+    );
     let mut highest_card = Card::get_highest_card(&all_cards);
 
     tied_ranks.push(highest_card.rank);
     hands = filter_hands(hands, tied_ranks.clone());
 
     // check the next highest card until all have been checked
-    for _ in 0..(config.count - 1) {
+    for n in 2..=config.count {
         if hands.len() <= 1 {
             return hands;
         }
-        if all_cards.len() == 0 {
+        if all_cards.is_empty() {
             break;
         }
         all_cards = get_cards(hands.clone());
         // remove the highest cards from the list of all cards
         all_cards = filter_cards(all_cards, tied_ranks.clone());
+        record_step(
+            steps,
+            TieBreakCriterion::Kicker(n),
+            &hands,
+            kicker(&tied_ranks),
+        );
         highest_card = Card::get_highest_card(&all_cards);
         tied_ranks.push(highest_card.rank);
         hands = filter_hands(hands, tied_ranks.clone());
@@ -743,37 +2926,49 @@ fn untie_highest(hands: Vec<PokerHand>, config: Option<UntieHighestConfig>) -> V
     hands
 }
 
-fn untie_straight(hands: Vec<PokerHand>) -> Vec<PokerHand> {
+fn untie_straight<'a>(
+    hands: Vec<PokerHand<'a>>,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
+    record_step(steps, TieBreakCriterion::Sequence, &hands, |hand| {
+        hand.sequence.map(|seq| seq.rank)
+    });
     let mut sequences: Vec<(Sequence, &PokerHand)> = hands
         .iter()
-        .filter_map(|hand| hand.sequence.as_ref().map(|seq| (seq.clone(), hand)))
+        .filter_map(|hand| hand.sequence.as_ref().map(|seq| (*seq, hand)))
         .collect();
 
-    sequences.sort_by(|a, b| b.0.rank.cmp(&a.0.rank));
+    sequences.sort_by_key(|a| Reverse(a.0.rank));
 
     let highest_sequence_rank = sequences[0].0.rank;
 
     sequences
         .into_iter()
         .filter(|(seq, _)| seq.rank == highest_sequence_rank)
-        .map(|(_, hand)| hand.clone())
+        .map(|(_, hand)| *hand)
         .collect()
 }
 
-fn untie_four(hands: Vec<PokerHand>) -> Vec<PokerHand> {
+fn untie_four<'a>(
+    hands: Vec<PokerHand<'a>>,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
+    record_step(steps, TieBreakCriterion::Quadruplet, &hands, |hand| {
+        hand.quadruplet.map(|quad| quad.rank)
+    });
     let mut quadruplets: Vec<(Quadruplet, &PokerHand)> = hands
         .iter()
-        .filter_map(|hand| hand.quadruplet.as_ref().map(|quad| (quad.clone(), hand)))
+        .filter_map(|hand| hand.quadruplet.as_ref().map(|quad| (*quad, hand)))
         .collect();
 
-    quadruplets.sort_by(|a, b| b.0.rank.cmp(&a.0.rank));
+    quadruplets.sort_by_key(|a| Reverse(a.0.rank));
 
     let highest_quadruplet_rank = quadruplets[0].0.rank;
 
     let hands: Vec<PokerHand<'_>> = quadruplets
         .into_iter()
         .filter(|(quad, _)| quad.rank == highest_quadruplet_rank)
-        .map(|(_, hand)| hand.clone())
+        .map(|(_, hand)| *hand)
         .collect();
 
     untie_highest(
@@ -782,23 +2977,30 @@ fn untie_four(hands: Vec<PokerHand>) -> Vec<PokerHand> {
             count: 1,
             ranks: vec![highest_quadruplet_rank],
         }),
+        steps,
     )
 }
 
-fn untie_one_pair(hands: Vec<PokerHand>) -> Vec<PokerHand> {
+fn untie_one_pair<'a>(
+    hands: Vec<PokerHand<'a>>,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
+    record_step(steps, TieBreakCriterion::Pair, &hands, |hand| {
+        hand.pairs.map(|(pair, _)| pair.rank)
+    });
     let mut pairs: Vec<((Pair, Option<Pair>), &PokerHand)> = hands
         .iter()
-        .filter_map(|hand| hand.pairs.as_ref().map(|pairs| (pairs.clone(), hand)))
+        .filter_map(|hand| hand.pairs.as_ref().map(|pairs| (*pairs, hand)))
         .collect();
 
-    pairs.sort_by(|a, b| b.0 .0.rank.cmp(&a.0 .0.rank));
+    pairs.sort_by_key(|a| Reverse(a.0 .0.rank));
 
     let highest_pair_rank = pairs[0].0 .0.rank;
 
     let hands: Vec<PokerHand<'_>> = pairs
         .into_iter()
         .filter(|((pair, _), _)| pair.rank == highest_pair_rank)
-        .map(|(_, hand)| hand.clone())
+        .map(|(_, hand)| *hand)
         .collect();
 
     untie_highest(
@@ -807,23 +3009,33 @@ fn untie_one_pair(hands: Vec<PokerHand>) -> Vec<PokerHand> {
             count: 3,
             ranks: vec![highest_pair_rank],
         }),
+        steps,
     )
 }
 
-fn untie_two_pairs(hands: Vec<PokerHand>) -> Vec<PokerHand> {
+fn untie_two_pairs<'a>(
+    hands: Vec<PokerHand<'a>>,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
+    record_step(steps, TieBreakCriterion::Pair, &hands, |hand| {
+        hand.pairs.map(|(pair, _)| pair.rank)
+    });
     let mut pairs: Vec<((Pair, Option<Pair>), &PokerHand)> = hands
         .iter()
-        .filter_map(|hand| hand.pairs.as_ref().map(|pairs| (pairs.clone(), hand)))
+        .filter_map(|hand| hand.pairs.as_ref().map(|pairs| (*pairs, hand)))
         .collect();
 
-    pairs.sort_by(|a, b| b.0 .0.rank.cmp(&a.0 .0.rank));
+    pairs.sort_by_key(|a| Reverse(a.0 .0.rank));
 
     let first_pair_rank = pairs[0].0 .0.rank;
 
-    pairs = pairs
-        .into_iter()
-        .filter(|((pair, _), _)| pair.rank == first_pair_rank)
-        .collect();
+    pairs.retain(|((pair, _), _)| pair.rank == first_pair_rank);
+
+    let remaining: Vec<PokerHand> = pairs.iter().map(|(_, hand)| **hand).collect();
+    record_step(steps, TieBreakCriterion::SecondPair, &remaining, |hand| {
+        hand.pairs
+            .and_then(|(_, second_pair)| second_pair.map(|pair| pair.rank))
+    });
 
     let second_pair_rank = pairs
         .iter()
@@ -831,17 +3043,14 @@ fn untie_two_pairs(hands: Vec<PokerHand>) -> Vec<PokerHand> {
         .max();
 
     if let Some(second_pair_rank) = second_pair_rank {
-        pairs = pairs
-            .into_iter()
-            .filter(|((_, second_pair), _)| {
-                second_pair
-                    .as_ref()
-                    .map_or(false, |pair| pair.rank == second_pair_rank)
-            })
-            .collect();
+        pairs.retain(|((_, second_pair), _)| {
+            second_pair
+                .as_ref()
+                .is_some_and(|pair| pair.rank == second_pair_rank)
+        });
     }
 
-    let hands: Vec<PokerHand> = pairs.into_iter().map(|(_, hand)| hand.clone()).collect();
+    let hands: Vec<PokerHand> = pairs.into_iter().map(|(_, hand)| *hand).collect();
 
     if hands.len() > 1 {
         untie_highest(
@@ -850,31 +3059,35 @@ fn untie_two_pairs(hands: Vec<PokerHand>) -> Vec<PokerHand> {
                 count: 1,
                 ranks: vec![first_pair_rank, second_pair_rank.unwrap_or(first_pair_rank)],
             }),
+            steps,
         )
     } else {
         hands
     }
 }
 
-fn untie_three(hands: Vec<PokerHand>) -> Vec<PokerHand> {
+fn untie_three<'a>(
+    hands: Vec<PokerHand<'a>>,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
+    record_step(steps, TieBreakCriterion::Triplet, &hands, |hand| {
+        hand.triplet.map(|triplet| triplet.rank)
+    });
     let mut triplets: Vec<(Triplet, &PokerHand)> = hands
         .iter()
-        .map(|hand| (hand.triplet.unwrap().clone(), hand))
+        .map(|hand| (hand.triplet.unwrap(), hand))
         .collect();
 
-    triplets.sort_by(|a, b| b.0.rank.cmp(&a.0.rank));
+    triplets.sort_by_key(|a| Reverse(a.0.rank));
 
     let highest_triplet_rank = triplets[0].0.rank;
 
-    triplets = triplets
-        .into_iter()
-        .filter(|(triplet, _)| triplet.rank == highest_triplet_rank)
-        .collect();
+    triplets.retain(|(triplet, _)| triplet.rank == highest_triplet_rank);
 
     let hands: Vec<PokerHand<'_>> = triplets
         .clone()
         .into_iter()
-        .map(|(_, hand)| hand.clone())
+        .map(|(_, hand)| *hand)
         .collect();
 
     untie_highest(
@@ -883,37 +3096,38 @@ fn untie_three(hands: Vec<PokerHand>) -> Vec<PokerHand> {
             count: 2,
             ranks: vec![highest_triplet_rank],
         }),
+        steps,
     )
 }
 
-fn untie_full_house(hands: Vec<PokerHand>) -> Vec<PokerHand> {
-    let mut triplets: Vec<(Triplet, &PokerHand)> = hands
+fn untie_full_house<'a>(
+    hands: Vec<PokerHand<'a>>,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
+    record_step(steps, TieBreakCriterion::Triplet, &hands, |hand| {
+        hand.triplet.map(|triplet| triplet.rank)
+    });
+    let highest_triplet_rank = hands
         .iter()
-        .map(|hand| (hand.triplet.unwrap().clone(), hand))
-        .collect();
-
-    triplets.sort_by(|a, b| b.0.rank.cmp(&a.0.rank));
-
-    let highest_triplet_rank = triplets[0].0.rank;
-
-    triplets = triplets
+        .filter_map(|hand| hand.triplet.map(|triplet| triplet.rank))
+        .max();
+    let hands: Vec<PokerHand<'_>> = hands
         .into_iter()
-        .filter(|(triplet, _)| triplet.rank == highest_triplet_rank)
+        .filter(|hand| hand.triplet.map(|triplet| triplet.rank) == highest_triplet_rank)
         .collect();
 
-    let hands: Vec<PokerHand<'_>> = triplets
-        .clone()
+    // with several decks or shared cards the triplets can match, then the pair decides
+    record_step(steps, TieBreakCriterion::Pair, &hands, |hand| {
+        hand.pairs.map(|(pair, _)| pair.rank)
+    });
+    let highest_pair_rank = hands
+        .iter()
+        .filter_map(|hand| hand.pairs.map(|(pair, _)| pair.rank))
+        .max();
+    hands
         .into_iter()
-        .map(|(_, hand)| hand.clone())
-        .collect();
-
-    untie_highest(
-        hands,
-        Some(UntieHighestConfig {
-            count: 2,
-            ranks: vec![highest_triplet_rank],
-        }),
-    )
+        .filter(|hand| hand.pairs.map(|(pair, _)| pair.rank) == highest_pair_rank)
+        .collect()
 }
 
 /// Given a list of poker hands, return a list of those hands which win.
@@ -921,27 +3135,203 @@ fn untie_full_house(hands: Vec<PokerHand>) -> Vec<PokerHand> {
 /// Note the type signature: this function should return _the same_ reference to
 /// the winning hand(s) as were passed in, not reconstructed strings which happen to be equal.
 pub fn winning_hands<'a>(hands: &[&'a str]) -> Vec<&'a str> {
-    let mut scored_hands: Vec<PokerHand> = hands
+    get_winners(hands, StraightPolicy::default(), &mut vec![])
+        .iter()
+        .map(|hand| hand.raw.unwrap_or_default())
+        .collect()
+}
+
+// One hand of a showdown, as rendered by `showdown_json`.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct ShowdownEntry<'a> {
+    hand: &'a str,
+    category: String,
+    strength_key: u32,
+    winner: bool,
+}
+
+/// Renders a showdown between the hands as a JSON object, e.g.
+/// `{"hands":[{"hand":"4S 4H 9D QC 2D","category":"Pair of Fours","strength_key":...,"winner":true}]}`,
+/// listing every hand in input order with its `describe` category and `strength_key`.
+#[cfg(feature = "serde")]
+pub fn showdown_json(hands: &[&str]) -> String {
+    let winners = winning_hands(hands);
+    let entries: Vec<ShowdownEntry> = hands
+        .iter()
+        .map(|&hand_str| {
+            let hand = PokerHand::new(hand_str);
+            ShowdownEntry {
+                hand: hand_str,
+                category: hand.describe(),
+                strength_key: hand.strength_key(),
+                winner: winners.contains(&hand_str),
+            }
+        })
+        .collect();
+    serde_json::json!({ "hands": entries }).to_string()
+}
+
+/// Given a list of poker hands, return those classified exactly as `rank`, in input order.
+pub fn hands_with_rank<'a>(hands: &[&'a str], rank: PokerHandRank) -> Vec<&'a str> {
+    hands
+        .iter()
+        .filter(|&&hand_str| PokerHand::new(hand_str).rank == rank)
+        .copied()
+        .collect()
+}
+
+/// Given a list of poker hands, return a one-line reason why the winners took the pot, e.g.
+/// "Full House, Aces over Kings", or "Chopped between 2 hands: Flush, Ace high" on a tie.
+/// Returns `None` if there are no hands.
+pub fn winning_reason(hands: &[&str]) -> Option<String> {
+    if hands.is_empty() {
+        return None;
+    }
+    let winners = winning_hands(hands);
+    let description = PokerHand::new(winners[0]).describe();
+    match winners.len() {
+        1 => Some(description),
+        n => Some(format!("Chopped between {} hands: {}", n, description)),
+    }
+}
+
+/// Given a list of poker hands, return the final standings as `(place, tied_hands)` groups,
+/// from the 1st place downward. Tied hands share a place and the places they take up are
+/// skipped, so two hands tying for 2nd are followed by the 4th place.
+pub fn placements<'a>(hands: &[&'a str]) -> Vec<(usize, Vec<&'a str>)> {
+    let mut ranked: Vec<PokerHand> = hands.iter().map(|hand| PokerHand::new(hand)).collect();
+    ranked.sort_by(|a, b| b.compare_strength(a));
+
+    let mut standings: Vec<(usize, Vec<&'a str>)> = vec![];
+    for (index, hand) in ranked.iter().enumerate() {
+        let raw = hand.raw.unwrap_or_default();
+        let ties_previous =
+            index > 0 && ranked[index - 1].compare_strength(hand) == Ordering::Equal;
+        match standings.last_mut() {
+            Some((_, tied)) if ties_previous => tied.push(raw),
+            _ => standings.push((index + 1, vec![raw])),
+        }
+    }
+    standings
+}
+
+/// Given a list of poker hands, return the positions of the hands which win, in ascending order.
+pub fn winning_indices(hands: &[&str]) -> Vec<usize> {
+    // textually identical hands always tie, so matching the winners by string is enough
+    let winners = winning_hands(hands);
+    hands
         .iter()
-        .map(|&hand_str| PokerHand::new(hand_str))
+        .enumerate()
+        .filter(|(_, hand)| winners.contains(hand))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// The same as `winning_indices`.
+pub fn winning_hands_indices(hands: &[&str]) -> Vec<usize> {
+    winning_indices(hands)
+}
+
+/// Given a list of poker hands, return the sequence of decisions taken to break the tie
+/// between the hands of the highest category, in the order they were made.
+///
+/// Hands of a lower category are discarded before any tie-break step is taken.
+pub fn explain_tiebreak<'a>(hands: &[&'a str]) -> Vec<TieBreakStep<'a>> {
+    let mut steps = vec![];
+    get_winners(hands, StraightPolicy::default(), &mut steps);
+    steps
+}
+
+/// Given a list of poker hands, return the hands which win when ignoring all combinations,
+/// comparing their five cards from the highest to the lowest, with aces high.
+///
+/// This compares card by card, so duplicated ranks count as many times as they appear:
+/// "KS KH 5C 3D 2H" beats "KC 8D 6S 4H 3S" because its second card is a king.
+pub fn winning_hands_high_card_only<'a>(hands: &[&'a str]) -> Vec<&'a str> {
+    let ranked_hands: Vec<([CardRank; 5], &'a str)> = hands
+        .iter()
+        .map(|&raw| {
+            let mut ranks = PokerHand::cards_from_raw_string(raw).map(|card| card.rank);
+            ranks.reverse(); // sort descending
+            (ranks, raw)
+        })
         .collect();
 
-    scored_hands.sort_by(|a, b| a.rank.cmp(&b.rank));
-    let highest_rank: PokerHandRank = scored_hands.last().unwrap().rank;
-    let winners = untie(scored_hands, highest_rank);
-    winners
+    let highest_ranks = ranked_hands.iter().map(|(ranks, _)| *ranks).max();
+    ranked_hands
+        .into_iter()
+        .filter(|(ranks, _)| Some(*ranks) == highest_ranks)
+        .map(|(_, raw)| raw)
+        .collect()
+}
+
+/// Like `winning_hands`, but only accepts the Ace to 5 and 10 to Ace straights if the policy allows them.
+pub fn winning_hands_with_policy<'a>(hands: &[&'a str], policy: StraightPolicy) -> Vec<&'a str> {
+    get_winners(hands, policy, &mut vec![])
+        .iter()
+        .map(|hand| hand.raw.unwrap_or_default())
+        .collect()
+}
+
+/// Like `winning_hands`, but orders the categories by `ranking`. Hands of the same category
+/// are still compared by their ranks.
+pub fn winning_hands_with_ranking<'a>(
+    hands: &[&'a str],
+    ranking: &CategoryRanking,
+) -> Vec<&'a str> {
+    let scored: Vec<PokerHand> = hands.iter().map(|hand| PokerHand::new(hand)).collect();
+    let strength = |hand: &PokerHand| (ranking.index(hand.rank), hand.tiebreak_ranks());
+    let Some(best) = scored.iter().map(strength).max() else {
+        return vec![];
+    };
+    scored
+        .iter()
+        .filter(|hand| strength(hand) == best)
+        .map(|hand| hand.raw.unwrap_or_default())
+        .collect()
+}
+
+fn get_winners<'a>(
+    hands: &[&'a str],
+    policy: StraightPolicy,
+    steps: &mut Vec<TieBreakStep<'a>>,
+) -> Vec<PokerHand<'a>> {
+    let scored_hands: Vec<PokerHand> = hands
         .iter()
-        .filter(|hand| hand.rank == highest_rank)
-        .map(|hand| hand.raw)
+        .map(|&hand_str| PokerHand::new_with_policy(hand_str, policy))
+        .collect();
+
+    let best: PokerHand = *scored_hands.iter().max().unwrap();
+    // the winners are decided by the ordering of the hands, untie only records the steps
+    // taken to break the tie, for `explain_tiebreak`
+    untie(scored_hands.clone(), best.rank, steps);
+    scored_hands
+        .into_iter()
+        .filter(|hand| hand.compare_strength(&best) == Ordering::Equal)
         .collect()
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq)]
+#[allow(unused)]
 pub struct Triplet {
     rank: CardRank,
     cards: [Card; 3],
 }
 
+impl Triplet {
+    pub fn rank(&self) -> CardRank {
+        self.rank
+    }
+}
+
+// Equal by rank alone, like the ordering, whatever the suites of the cards.
+impl PartialEq for Triplet {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
 impl Ord for Triplet {
     fn cmp(&self, other: &Self) -> Ordering {
         self.rank.cmp(&other.rank)
@@ -954,12 +3344,26 @@ impl PartialOrd for Triplet {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq)]
+#[allow(unused)]
 pub struct Quadruplet {
     rank: CardRank,
     cards: [Card; 4],
 }
 
+impl Quadruplet {
+    pub fn rank(&self) -> CardRank {
+        self.rank
+    }
+}
+
+// Equal by rank alone, like the ordering, whatever the suites of the cards.
+impl PartialEq for Quadruplet {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
 impl Ord for Quadruplet {
     fn cmp(&self, other: &Self) -> Ordering {
         self.rank.cmp(&other.rank)
@@ -972,12 +3376,20 @@ impl PartialOrd for Quadruplet {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq)]
+#[allow(unused)]
 pub struct Pair {
     rank: CardRank,
     cards: [Card; 2],
 }
 
+// Equal by rank alone, like the ordering, whatever the suites of the cards.
+impl PartialEq for Pair {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
 impl Ord for Pair {
     fn cmp(&self, other: &Self) -> Ordering {
         self.rank.cmp(&other.rank)
@@ -990,12 +3402,32 @@ impl PartialOrd for Pair {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq)]
 pub struct Sequence {
     rank: CardRank,
     cards: [Card; 5],
 }
 
+impl Sequence {
+    /// Whether this is the Ace to 5 straight, where the Ace is played low.
+    pub fn is_wheel(&self) -> bool {
+        self.cards.iter().any(|card| card.rank == CardRank::LowAce)
+    }
+
+    /// Whether this is the Ace to 5 straight flush in `suite`, the steel wheel, which is
+    /// the weakest straight flush.
+    pub fn is_steel_wheel(&self, suite: CardSuite) -> bool {
+        self.is_wheel() && self.cards.iter().all(|card| card.suite == suite)
+    }
+}
+
+// Equal by rank alone, like the ordering, whatever the suites of the cards.
+impl PartialEq for Sequence {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank == other.rank
+    }
+}
+
 impl Ord for Sequence {
     fn cmp(&self, other: &Self) -> Ordering {
         self.rank.cmp(&other.rank)
@@ -352,11 +352,1686 @@ fn test_card_rank() {
 
 #[test]
 fn test_low_ace() {
-    let hand = PokerHand::new("AH 2H 3H 4H 5H");
+    let hand = PokerHand::new("AH 2C 3D 4S 5H");
+    let sequence = PokerHand::get_sequence(*hand.cards()).unwrap();
 
-    assert_eq!(*hand.cards()[0].rank(), CardRank::LowAce);
-    assert_eq!(*hand.cards()[1].rank(), CardRank::Two);
-    assert_eq!(*hand.cards()[2].rank(), CardRank::Three);
-    assert_eq!(*hand.cards()[3].rank(), CardRank::Four);
-    assert_eq!(*hand.cards()[4].rank(), CardRank::Five);
+    assert!(sequence.is_wheel());
+    assert!(
+        sequence < PokerHand::get_sequence(cards("2C 3D 4S 5H 6H").try_into().unwrap()).unwrap()
+    );
+    assert!(hand.cards().contains(&Card::new("AH")));
+}
+
+#[test]
+fn test_adjacent_categories_are_ordered() {
+    // each entry is (stronger hand, weaker hand) for two adjacent categories
+    let ladder = [
+        ("10S JS QS KS AS", "5H 6H 7H 8H 9H"), // royal flush vs straight flush
+        ("5H 6H 7H 8H 9H", "AC AD AH AS KD"),  // straight flush vs four of a kind
+        ("2C 2D 2H 2S 3D", "AC AD AH KS KD"),  // four of a kind vs full house
+        ("2C 2D 2H 3S 3D", "AH KH QH JH 9H"),  // full house vs flush
+        ("2D 3D 4D 5D 7D", "10C JD QH KS AC"), // flush vs straight
+        ("AC 2D 3H 4S 5C", "AS AD AH KS QD"),  // straight vs three of a kind
+        ("2C 2D 2H 3S 4D", "AC AD KH KS QD"),  // three of a kind vs two pairs
+        ("2C 2D 3H 3S 4D", "AC AD KH QS JD"),  // two pairs vs one pair
+        ("2C 2D 3H 4S 5D", "AC KD QH JS 9D"),  // one pair vs high card
+    ];
+
+    for (stronger, weaker) in ladder {
+        assert_eq!(winning_hands(&[stronger, weaker]), vec![stronger]);
+        assert_eq!(winning_hands(&[weaker, stronger]), vec![stronger]);
+    }
+}
+
+#[test]
+fn test_explain_tiebreak_on_kicker() {
+    let input = &["4H 4S AH JC 3D", "4C 4D AS 5D 6C"];
+    let steps = explain_tiebreak(input);
+
+    let criteria: Vec<TieBreakCriterion> = steps.iter().map(|step| step.criterion()).collect();
+    assert_eq!(
+        criteria,
+        vec![
+            TieBreakCriterion::Pair,
+            TieBreakCriterion::Kicker(1),
+            TieBreakCriterion::Kicker(2),
+        ]
+    );
+
+    assert_eq!(
+        steps[0].compared(),
+        &[(input[0], CardRank::Four), (input[1], CardRank::Four)]
+    );
+    assert!(steps[0].eliminated().is_empty());
+    assert_eq!(
+        steps[1].compared(),
+        &[(input[0], CardRank::Ace), (input[1], CardRank::Ace)]
+    );
+    assert!(steps[1].eliminated().is_empty());
+    assert_eq!(
+        steps[2].compared(),
+        &[(input[0], CardRank::Jack), (input[1], CardRank::Six)]
+    );
+    assert_eq!(steps[2].eliminated(), &[input[1]]);
+    assert_eq!(
+        steps[2].to_string(),
+        "compared kicker 2: J vs 6 -> eliminated \"4C 4D AS 5D 6C\""
+    );
+}
+
+#[test]
+fn test_card_tuple_conversions() {
+    let (rank, suite) = Card::new("QD").into();
+    assert_eq!(rank, CardRank::Queen);
+    assert_eq!(suite, CardSuite::Diamonds);
+
+    let card = Card::from((CardRank::Ten, CardSuite::Spades));
+    assert_eq!(card.to_string(), "10S");
+
+    let parts = (CardRank::Ace, CardSuite::Hearts);
+    assert_eq!(<(CardRank, CardSuite)>::from(Card::from(parts)), parts);
+}
+
+#[test]
+fn test_unseen_cards() {
+    let known: Vec<Card> = ["AH", "AS", "KD", "10C", "2C", "7H", "JS"]
+        .iter()
+        .map(|&card| Card::new(card))
+        .collect();
+    let unseen = unseen_cards(&known);
+
+    assert_eq!(unseen.len(), 45);
+    assert_eq!(unseen[0].to_string(), "3C");
+    assert!(unseen
+        .iter()
+        .all(|card| !["AH", "AS", "KD", "10C", "2C", "7H", "JS"]
+            .contains(&card.to_string().as_str())));
+    assert_eq!(unseen_cards(&[]).len(), 52);
+}
+
+#[test]
+fn test_winning_indices() {
+    let input = &[
+        "3H 4H 5C 6C JD",
+        "4D 5S 6S 8D 3C",
+        "2S 4C 7S 9H 10H",
+        "3S 4S 5D 6H JH",
+    ];
+    assert_eq!(winning_indices(input), vec![0, 3]);
+    assert_eq!(winning_indices(&["4D 5S 6S 8D 3C"]), vec![0]);
+}
+
+#[test]
+fn test_winning_hands_high_card_only() {
+    let input = &["2S 2H 5C 7D 9S", "AS 3H 6C 8D 10S"];
+    assert_eq!(winning_hands_high_card_only(input), vec!["AS 3H 6C 8D 10S"]);
+
+    let input = &["KS KH 5C 3D 2H", "KC 8D 6S 4H 3S"];
+    assert_eq!(winning_hands_high_card_only(input), vec!["KS KH 5C 3D 2H"]);
+
+    let input = &["2H 3H 4H 5H AH", "2S 3S 4S 5S 6S"];
+    assert_eq!(winning_hands_high_card_only(input), vec!["2H 3H 4H 5H AH"]);
+
+    let input = &["4D 5S 6S 8D 3C", "3H 4H 5C 6C 8S"];
+    assert_eq!(winning_hands_high_card_only(input), input.to_vec());
+}
+
+fn cards(cards: &str) -> Vec<Card> {
+    cards.split_whitespace().map(Card::new).collect()
+}
+
+#[test]
+fn test_plays_the_board() {
+    let board: [Card; 5] = cards("2H 5H 8H JH KH").try_into().unwrap();
+
+    let hole: [Card; 2] = cards("3C 4D").try_into().unwrap();
+    assert!(plays_the_board(&hole, &board));
+
+    let hole: [Card; 2] = cards("AH 3C").try_into().unwrap();
+    assert!(!plays_the_board(&hole, &board));
+
+    let board: [Card; 5] = cards("2C 3D 7H 9S KC").try_into().unwrap();
+    let hole: [Card; 2] = cards("AS QD").try_into().unwrap();
+    assert!(!plays_the_board(&hole, &board));
+}
+
+#[test]
+fn test_best_of() {
+    let hand = best_of(&cards("2C 3D 7H 7S 9H 7C 9D"));
+    assert_eq!(hand.to_string(), "\"7H 7S 7C 9H 9D\"");
+}
+
+#[test]
+fn test_hands_with_rank() {
+    let input = &[
+        "2H 5H 8H JH KH",
+        "4S 5S 6S 7S 8S",
+        "2D 3D 4D 5D 7D",
+        "2C 2D 2H 3S 3D",
+        "AC 3C 6C 9C QC",
+        "10C JD QH KS AC",
+    ];
+    assert_eq!(
+        hands_with_rank(input, PokerHandRank::Flush),
+        vec!["2H 5H 8H JH KH", "2D 3D 4D 5D 7D", "AC 3C 6C 9C QC"]
+    );
+    assert!(hands_with_rank(input, PokerHandRank::OnePair).is_empty());
+}
+
+#[test]
+fn test_card_strict_parsing() {
+    assert_eq!(
+        Card::try_new("ASX"),
+        Err(CardParseError::TrailingCharacters("ASX".to_string()))
+    );
+    assert_eq!(
+        Card::try_new("10HH"),
+        Err(CardParseError::TrailingCharacters("10HH".to_string()))
+    );
+    assert_eq!(
+        <(CardRank, CardSuite)>::from(Card::try_new("10H").unwrap()),
+        (CardRank::Ten, CardSuite::Hearts)
+    );
+
+    assert_eq!(
+        Card::try_new("A"),
+        Err(CardParseError::TooShort("A".to_string()))
+    );
+    assert_eq!(
+        Card::try_new("XS"),
+        Err(CardParseError::InvalidRank("XS".to_string()))
+    );
+    assert_eq!(
+        Card::try_new("AX"),
+        Err(CardParseError::InvalidSuite("AX".to_string()))
+    );
+}
+
+#[test]
+fn test_the_nuts() {
+    let nuts = the_nuts(&cards("9H 10H JH 2C 3D"));
+    assert_eq!(nuts.rank(), PokerHandRank::StraightFlush);
+    assert_eq!(*nuts.cards()[4].rank(), CardRank::King);
+
+    let nuts = the_nuts(&cards("2C 7D 9H"));
+    assert_eq!(nuts.rank(), PokerHandRank::ThreeOfAKind);
+    assert_eq!(*nuts.cards()[4].rank(), CardRank::Nine);
+}
+
+#[test]
+fn test_broadway_beats_king_high_straight() {
+    let input = &["10C JD QH KS AC", "9C 10D JH QS KC"];
+    assert_eq!(winning_hands(input), vec!["10C JD QH KS AC"]);
+    let sequence = PokerHand::get_sequence(PokerHand::new("10C JD QH KS AC").cards().to_owned());
+    assert!(sequence.is_some());
+}
+
+#[test]
+fn test_straight_policy() {
+    let wheel = "AC 2D 3H 4S 5C";
+    let broadway = "10C JD QH KS AC";
+    let trips = "2C 2D 2H 7S 9D";
+
+    let policies = [(true, true), (true, false), (false, true), (false, false)];
+    for (allow_wheel, allow_broadway) in policies {
+        let policy = StraightPolicy {
+            allow_wheel,
+            allow_broadway,
+        };
+
+        let expected = if allow_wheel { wheel } else { trips };
+        assert_eq!(
+            winning_hands_with_policy(&[wheel, trips], policy),
+            vec![expected]
+        );
+        let expected = if allow_broadway { broadway } else { trips };
+        assert_eq!(
+            winning_hands_with_policy(&[broadway, trips], policy),
+            vec![expected]
+        );
+
+        let cards = *PokerHand::new(wheel).cards();
+        assert_eq!(
+            PokerHand::get_sequence_with_policy(cards, policy).is_some(),
+            allow_wheel
+        );
+    }
+
+    let royal_flush = "10H JH QH KH AH";
+    let policy = StraightPolicy {
+        allow_wheel: true,
+        allow_broadway: false,
+    };
+    let cards = *PokerHand::new(royal_flush).cards();
+    assert_eq!(PokerHand::get_rank(cards), PokerHandRank::RoyalFlush);
+    assert_eq!(
+        PokerHand::get_rank_with_policy(cards, policy),
+        PokerHandRank::Flush
+    );
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_win_rate_vs_random() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let royal_flush: [Card; 5] = cards("10S JS QS KS AS").try_into().unwrap();
+    assert_eq!(win_rate_vs_random(&royal_flush, 1000, &mut rng), 1.0);
+
+    let seven_high: [Card; 5] = cards("2C 3D 4H 5S 7C").try_into().unwrap();
+    assert!(win_rate_vs_random(&seven_high, 1000, &mut rng) < 0.01);
+}
+
+#[test]
+fn test_straight_flush_draw() {
+    assert_eq!(
+        straight_flush_draw(&cards("6H 7H 8H 9H 2C")),
+        Some((
+            CardSuite::Hearts,
+            vec![
+                CardRank::Six,
+                CardRank::Seven,
+                CardRank::Eight,
+                CardRank::Nine
+            ]
+        ))
+    );
+    assert_eq!(straight_flush_draw(&cards("6H 7H 8S 9H 2H")), None);
+}
+
+#[test]
+fn test_straight_flush_draw_made() {
+    assert_eq!(straight_flush_draw(&cards("5H 6H 7H 8H 9H")), None);
+    assert_eq!(straight_flush_draw(&cards("AS 2S 3S 4S 5S 9H")), None);
+}
+
+#[test]
+fn test_straight_flush_draw_wheel() {
+    assert_eq!(
+        straight_flush_draw(&cards("AH 2H 3H 4H")),
+        Some((
+            CardSuite::Hearts,
+            vec![
+                CardRank::Two,
+                CardRank::Three,
+                CardRank::Four,
+                CardRank::Ace
+            ]
+        ))
+    );
+}
+
+#[test]
+fn test_canonical_id_ignores_card_order() {
+    assert_eq!(
+        PokerHand::new("4S 4H 2C 10D AS").canonical_id(),
+        PokerHand::new("AS 4H 10D 4S 2C").canonical_id()
+    );
+}
+
+#[test]
+fn test_canonical_id_differs_by_suite() {
+    assert_ne!(
+        PokerHand::new("4S 4H 2C 10D AS").canonical_id(),
+        PokerHand::new("4S 4H 2C 10D AH").canonical_id()
+    );
+}
+
+#[test]
+fn test_hand_contains_card() {
+    let hand = PokerHand::new("4S 5H AS 10D 2C");
+    assert!(hand.contains(&Card::new("AS")));
+    assert!(!hand.contains(&Card::new("AH")));
+}
+
+#[test]
+fn test_card_pairs() {
+    let pairs = card_pairs(&cards("2C 3D 4H 5S"));
+    assert_eq!(pairs.len(), 6);
+
+    let mut distinct: Vec<String> = pairs
+        .iter()
+        .map(|(a, b)| {
+            let mut pair = [a.to_string(), b.to_string()];
+            pair.sort();
+            pair.join(" ")
+        })
+        .collect();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 6);
+}
+
+#[test]
+fn test_signature() {
+    assert_eq!(PokerHand::new("3S 3H 3D 3C 10D").signature(), vec![4, 1]);
+    assert_eq!(PokerHand::new("3S 3H 3D 10C 10D").signature(), vec![3, 2]);
+    assert_eq!(PokerHand::new("3S 3H 3D 10C JD").signature(), vec![3, 1, 1]);
+    assert_eq!(
+        PokerHand::new("3S 3H 10D 10C JD").signature(),
+        vec![2, 2, 1]
+    );
+    assert_eq!(
+        PokerHand::new("3S 3H 10D QC JD").signature(),
+        vec![2, 1, 1, 1]
+    );
+    assert_eq!(
+        PokerHand::new("4D AH 3S 2D 5C").signature(),
+        vec![1, 1, 1, 1, 1]
+    );
+}
+
+#[test]
+fn test_get_rank_wild_without_wild_ranks() {
+    let no_wilds = WildConfig::default();
+    for hand in ["2S 2H 6D 7C 9S", "JS 10H 9H 8H 7H", "4D AH 3S 2D 5C"] {
+        let hand: [Card; 5] = cards(hand).try_into().unwrap();
+        assert_eq!(
+            PokerHand::get_rank_wild(hand, &no_wilds),
+            PokerHand::get_rank(hand)
+        );
+    }
+}
+
+#[test]
+fn test_get_rank_wild() {
+    let deuces = WildConfig {
+        ranks: vec![CardRank::Two],
+    };
+    let hand: [Card; 5] = cards("2S 2H 6D 6C 9S").try_into().unwrap();
+    assert_eq!(
+        PokerHand::get_rank_wild(hand, &deuces),
+        PokerHandRank::FourOfAKind
+    );
+
+    let jacks = WildConfig {
+        ranks: vec![CardRank::Jack],
+    };
+    let hand: [Card; 5] = cards("JS 10H 9H 8H 7H").try_into().unwrap();
+    assert_eq!(
+        PokerHand::get_rank_wild(hand, &jacks),
+        PokerHandRank::StraightFlush
+    );
+}
+
+#[test]
+fn test_pair_relation() {
+    let board = cards("9S 7D 2C");
+
+    let kings: [Card; 2] = cards("KH KD").try_into().unwrap();
+    assert_eq!(pair_relation(&kings, &board), Some(PairRelation::Overpair));
+
+    let top_pair: [Card; 2] = cards("AH 9D").try_into().unwrap();
+    assert_eq!(
+        pair_relation(&top_pair, &board),
+        Some(PairRelation::TopPair)
+    );
+
+    let nothing: [Card; 2] = cards("AH KD").try_into().unwrap();
+    assert_eq!(pair_relation(&nothing, &board), None);
+}
+
+#[test]
+fn test_suit_count() {
+    assert_eq!(PokerHand::new("2S 4S 5S 6S 7S").suit_count(), 1);
+    assert_eq!(PokerHand::new("2S 4H 5D 6C 7S").suit_count(), 4);
+    assert_eq!(PokerHand::new("2S 4H 5S 6H 7S").suit_count(), 2);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_deal_and_evaluate() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let hands = deal_and_evaluate(4, &mut StdRng::seed_from_u64(7)).unwrap();
+    assert_eq!(hands.len(), 4);
+
+    let mut dealt: Vec<String> = hands
+        .iter()
+        .flat_map(|hand| hand.cards().map(|card| card.to_string()))
+        .collect();
+    dealt.sort();
+    dealt.dedup();
+    assert_eq!(dealt.len(), 20);
+
+    for hand in &hands {
+        assert_eq!(hand.rank(), PokerHand::get_rank(*hand.cards()));
+    }
+}
+
+#[test]
+fn test_top_n_hands() {
+    let top = top_n_hands(&cards("KH QH JH 4C 4D"), 3);
+    assert_eq!(top.len(), 3);
+    assert_eq!(top[0].rank(), PokerHandRank::RoyalFlush);
+    assert_eq!(top[1].rank(), PokerHandRank::StraightFlush);
+    assert_eq!(top[2].rank(), PokerHandRank::FourOfAKind);
+}
+
+#[test]
+fn test_same_cards() {
+    let hand = PokerHand::new("4S 5H AS 10D 2C");
+    assert!(hand.same_cards(&PokerHand::new("10D 2C 4S AS 5H")));
+    assert!(!hand.same_cards(&PokerHand::new("4S 5H AH 10D 2C")));
+    assert!(!hand.same_cards(&PokerHand::new("4S 5H KS 10D 2C")));
+}
+
+#[test]
+fn test_straight_detection() {
+    let ranks = [
+        "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A",
+    ];
+    let suites = ["S", "H", "D", "C", "S"];
+    for low in 0..10 {
+        let hand: Vec<String> = (0..5)
+            .map(|i| format!("{}{}", ranks[low + i], suites[i]))
+            .collect();
+        let hand: [Card; 5] = cards(&hand.join(" ")).try_into().unwrap();
+        assert_eq!(PokerHand::get_rank(hand), PokerHandRank::Straight);
+    }
+
+    for hand in [
+        "QS KH AD 2C 3S",
+        "KS AH 2D 3C 4S",
+        "2S 3H 4D 5C 7S",
+        "2S 3H 4D 5C 5S",
+        "AS 2H 3D 4C 6S",
+    ] {
+        let hand: [Card; 5] = cards(hand).try_into().unwrap();
+        assert_ne!(PokerHand::get_rank(hand), PokerHandRank::Straight);
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_category_distribution() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let distribution =
+        category_distribution(&cards("2H 6H 9H QH"), 2000, &mut StdRng::seed_from_u64(3));
+    let flush = distribution[PokerHandRank::Flush.as_number() as usize - 1];
+    // 9 of the 48 remaining cards are hearts
+    assert!(flush > 0.12 && flush < 0.25);
+    assert!((distribution.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_blocks_nuts() {
+    let board = cards("KH 8H 4H 2C 7S");
+    assert!(blocks_nuts(&cards("AH 3C"), &board));
+    assert!(!blocks_nuts(&cards("QC JD"), &board));
+}
+
+#[test]
+fn test_suit_isomorph() {
+    let hearts = PokerHand::new("AH KH 7C 4D 2S").suit_isomorph();
+    assert_eq!(hearts, PokerHand::new("AS KS 7C 4D 2H").suit_isomorph());
+    assert_ne!(hearts, PokerHand::new("AH KS 7C 4D 2S").suit_isomorph());
+}
+
+#[test]
+fn test_outs_to_nuts() {
+    // the 2 of hearts pairs the board and makes quads possible
+    let outs: Vec<String> = outs_to_nuts(&cards("AH QH"), &cards("KH 8H 2C"))
+        .iter()
+        .map(|card| card.to_string())
+        .collect();
+    assert_eq!(outs, vec!["3H", "4H", "5H", "6H", "7H", "9H", "10H", "JH"]);
+}
+
+#[test]
+fn test_combinations() {
+    let seven = cards("2C 5D 7H 9S JC QD AS");
+    assert_eq!(combinations(&seven, 5).count(), 21);
+    for combination in combinations(&seven, 5) {
+        let mut distinct: Vec<String> = combination.iter().map(|card| card.to_string()).collect();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 5);
+    }
+    assert_eq!(combinations(&seven, 0).count(), 1);
+    assert_eq!(combinations(&seven, 8).count(), 0);
+}
+
+#[test]
+fn test_double_gutshot() {
+    assert_eq!(
+        double_gutshot(&cards("5C 7D 8H 9S JC")),
+        Some([CardRank::Six, CardRank::Ten])
+    );
+    assert_eq!(double_gutshot(&cards("5C 6D 7H 8S KC")), None);
+    assert_eq!(double_gutshot(&cards("5C 7D 8H 9S KC")), None);
+}
+
+#[test]
+fn test_placements() {
+    let hands = [
+        "4S 5H 6D 8C 10S",
+        "3S 3H 3D 8C 10D",
+        "2S 2H 4D 4C KD",
+        "2D 2C 4H 4S KS",
+        "4H 5C 6S 8D 10H",
+    ];
+    assert_eq!(
+        placements(&hands),
+        vec![
+            (1, vec!["3S 3H 3D 8C 10D"]),
+            (2, vec!["2S 2H 4D 4C KD", "2D 2C 4H 4S KS"]),
+            (4, vec!["4S 5H 6D 8C 10S", "4H 5C 6S 8D 10H"]),
+        ]
+    );
+}
+
+#[test]
+fn test_is_monster() {
+    assert!(PokerHand::new("2S 2H 2D 3C 3D").is_monster(0.9));
+    assert!(!PokerHand::new("2S 2H 4D 5C 7D").is_monster(0.9));
+}
+
+#[test]
+fn test_percentile_bounds() {
+    assert_eq!(PokerHand::new("2S 3H 4D 5C 7D").percentile(), 0.0);
+    assert!(PokerHand::new("10S JS QS KS AS").percentile() > 0.999);
+}
+
+#[test]
+fn test_triplet_of_aces_is_ace_high() {
+    let hand: [Card; 5] = cards("AS AH AD KC QD").try_into().unwrap();
+    assert_eq!(
+        PokerHand::get_triplet(hand).map(|triplet| triplet.rank()),
+        Some(CardRank::Ace)
+    );
+
+    let hand: [Card; 5] = cards("AS AH AD KC KD").try_into().unwrap();
+    assert_eq!(
+        PokerHand::get_triplet(hand).map(|triplet| triplet.rank()),
+        Some(CardRank::Ace)
+    );
+}
+
+#[test]
+fn test_parse_range() {
+    assert_eq!(
+        parse_range("QQ+"),
+        Ok(vec![
+            (CardRank::Queen, CardRank::Queen, false),
+            (CardRank::King, CardRank::King, false),
+            (CardRank::Ace, CardRank::Ace, false),
+        ])
+    );
+    assert_eq!(
+        parse_range("AKs"),
+        Ok(vec![(CardRank::Ace, CardRank::King, true)])
+    );
+    assert_eq!(
+        parse_range("AKs, T9o"),
+        Ok(vec![
+            (CardRank::Ace, CardRank::King, true),
+            (CardRank::Ten, CardRank::Nine, false),
+        ])
+    );
+}
+
+#[test]
+fn test_parse_range_errors() {
+    assert_eq!(
+        parse_range("QQs"),
+        Err(RangeParseError::SuitedPair("QQs".to_string()))
+    );
+    assert_eq!(
+        parse_range("AX"),
+        Err(RangeParseError::InvalidRank("AX".to_string()))
+    );
+}
+
+#[test]
+fn test_top_kicker() {
+    let kicker = PokerHand::new("4S 4H 9D QC 2D").top_kicker();
+    assert_eq!(kicker.map(|card| card.to_string()), Some("QC".to_string()));
+
+    assert!(PokerHand::new("2S 4S 5S 6S 7S").top_kicker().is_none());
+}
+
+#[test]
+fn test_padded_cards_line_up() {
+    assert_eq!(Card::new("10S").to_padded_string(3), "10S");
+    assert_eq!(Card::new("JS").to_padded_string(3), "JS ");
+
+    let hands = PokerHandVec::from(vec![
+        PokerHand::new("10S 2H 4D 8C JS"),
+        PokerHand::new("3S 5H 7D 9C 10H"),
+    ]);
+    assert_eq!(
+        format!("{:3}", hands),
+        "2H  4D  8C  10S JS \n3S  5H  7D  9C  10H"
+    );
+}
+
+#[test]
+fn test_hands_beating_range() {
+    let board = cards("KH 8D 4C 2S 7H");
+    let mut target = board.clone();
+    target.extend(cards("AS KD"));
+    let target = best_of(&target);
+
+    let range = parse_range("AA, KQo").unwrap();
+    assert_eq!(hands_beating_range(&target, &range, &board), 3);
+
+    // brute force over every pair of hole cards left
+    let beating = card_pairs(&unseen_cards(&cards("KH 8D 4C 2S 7H AS KD")))
+        .into_iter()
+        .filter(|(first, second)| {
+            let ranks = [first.rank(), second.rank()];
+            let aces = ranks == [&CardRank::Ace, &CardRank::Ace];
+            let king_queen = ranks.contains(&&CardRank::King) && ranks.contains(&&CardRank::Queen);
+            let offsuit = first.to_string().chars().last() != second.to_string().chars().last();
+            aces || (king_queen && offsuit)
+        })
+        .filter(|(first, second)| {
+            let mut cards = board.clone();
+            cards.extend([*first, *second]);
+            best_of(&cards).percentile() > target.percentile()
+        })
+        .count();
+    assert_eq!(beating, 3);
+}
+
+#[test]
+fn test_sequence_is_wheel() {
+    let is_wheel = |hand: &str| {
+        let hand: [Card; 5] = cards(hand).try_into().unwrap();
+        PokerHand::get_sequence(hand).unwrap().is_wheel()
+    };
+    assert!(is_wheel("4D AH 3S 2D 5C"));
+    assert!(!is_wheel("4D 6H 3S 2D 5C"));
+    assert!(!is_wheel("10D AH QS KD JC"));
+}
+
+#[test]
+fn test_showdown_ev() {
+    // calling 10 to win 30 needs to win a quarter of the time
+    assert!(showdown_ev(0.25, 30.0, 10.0).abs() < 1e-9);
+    assert!((showdown_ev(0.5, 30.0, 10.0) - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_strength_tier() {
+    let tier = |hand: &str| PokerHand::new(hand).strength_tier();
+    assert_eq!(tier("2S 4H 6D 8C AS"), StrengthTier::Trash);
+    assert_eq!(tier("9S 9H 6D 8C AS"), StrengthTier::Trash);
+    assert_eq!(tier("10S 10H 6D 8C AS"), StrengthTier::Marginal);
+    assert_eq!(tier("2S 2H 3D 3C AS"), StrengthTier::Strong);
+    assert_eq!(tier("2S 2H 2D 3C AS"), StrengthTier::Strong);
+    assert_eq!(tier("4D AH 3S 2D 5C"), StrengthTier::Premium);
+    assert_eq!(tier("2S 4S 6S 8S AS"), StrengthTier::Premium);
+    assert_eq!(tier("2S 2H 2D 3C 3S"), StrengthTier::Monster);
+    assert_eq!(tier("10S JS QS KS AS"), StrengthTier::Monster);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_deck_string_round_trip() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut deck = Deck::new();
+    deck.shuffle(&mut StdRng::seed_from_u64(11));
+    deck.deal(3);
+
+    let restored = Deck::from_string(&deck.to_string()).unwrap();
+    assert_eq!(restored.len(), 49);
+    assert_eq!(restored.to_string(), deck.to_string());
+}
+
+#[test]
+fn test_deck_from_invalid_string() {
+    assert_eq!(
+        Deck::from_string("2C 3D 1X").unwrap_err(),
+        CardParseError::InvalidRank("1X".to_string())
+    );
+}
+
+#[test]
+fn test_can_add() {
+    let existing = cards("4S 5H AS");
+    assert!(!can_add(&existing, Card::new("AS")));
+    assert!(can_add(&existing, Card::new("AH")));
+    assert!(!can_add(&cards("4S 5H AS 10D 2C"), Card::new("3C")));
+}
+
+#[test]
+fn test_winning_reason() {
+    assert_eq!(
+        winning_reason(&["AS AH AD KC KD", "2S 4S 6S 8S 10S"]),
+        Some("Full House, Aces over Kings".to_string())
+    );
+    assert_eq!(
+        winning_reason(&["2S 4S 6S 8S AS", "2H 4H 6H 8H AH", "KS KH 3D 4C 5D"]),
+        Some("Chopped between 2 hands: Flush, Ace high".to_string())
+    );
+    assert_eq!(winning_reason(&[]), None);
+}
+
+#[test]
+fn test_describe() {
+    assert_eq!(PokerHand::new("6S 6H 3D 4C 8D").describe(), "Pair of Sixes");
+    assert_eq!(
+        PokerHand::new("4D AH 3S 2D 5C").describe(),
+        "Straight, Five high"
+    );
+}
+
+#[test]
+fn test_color_split() {
+    assert_eq!(PokerHand::new("2H 4H 6H 8H 10H").color_split(), (5, 0));
+    assert_eq!(PokerHand::new("2H 4D 6S 8C 10H").color_split(), (3, 2));
+    assert_eq!(PokerHand::new("2S 4S 6C 8S 10C").color_split(), (0, 5));
+}
+
+#[test]
+fn test_result_against() {
+    let hand = |hand: &str| -> [Card; 5] { cards(hand).try_into().unwrap() };
+    let my = hand("2S 4S 6S 8S AS");
+
+    assert_eq!(
+        result_against(&my, &[hand("KS KH 3D 4C 5D"), hand("2H 3H 4D 5C 7D")]),
+        ShowdownResult::Win
+    );
+    assert_eq!(
+        result_against(&my, &[hand("KS KH 3D 4C 5D"), hand("2H 4H 6H 8H AH")]),
+        ShowdownResult::Tie(1)
+    );
+    assert_eq!(
+        result_against(&my, &[hand("KS KH KD 4C 4D"), hand("2H 4H 6H 8H AH")]),
+        ShowdownResult::Lose
+    );
+}
+
+#[test]
+fn test_rank_span() {
+    let straight = PokerHand::new("4D 6H 3S 2D 5C");
+    assert_eq!(straight.rank_span(), 4);
+    assert!(straight.is_connected());
+
+    let broadway = PokerHand::new("10D AH QS KD JC");
+    assert_eq!(broadway.rank_span(), 4);
+    assert!(broadway.is_connected());
+
+    let scattered = PokerHand::new("2D 7H 9S KD 4C");
+    assert_eq!(scattered.rank_span(), 11);
+    assert!(!scattered.is_connected());
+}
+
+#[test]
+fn test_smallest_winner_over() {
+    let target = PokerHand::new("2S 2H 3D 4C 6D");
+    let winner = smallest_winner_over(&target, &[]).unwrap();
+
+    // the next pair of Twos up, with a 5 instead of the 4 kicker
+    let mut ranks: Vec<String> = winner
+        .cards()
+        .iter()
+        .map(|card| card.rank().to_string())
+        .collect();
+    ranks.sort();
+    assert_eq!(ranks, vec!["2", "2", "3", "5", "6"]);
+    assert!(!winner.contains(&Card::new("2S")) && !winner.contains(&Card::new("2H")));
+    assert!(winner.percentile() > target.percentile());
+
+    assert!(smallest_winner_over(&PokerHand::new("10S JS QS KS AS"), &[]).is_none());
+}
+
+#[test]
+fn test_best_of_three_pairs() {
+    let seven = cards("4S KS 9D 4H KH 9C QD");
+    assert_eq!(count_pairs(&seven), 3);
+
+    let best = best_of(&seven);
+    assert_eq!(best.describe(), "Two Pairs, Kings and Nines");
+    assert_eq!(
+        best.top_kicker().map(|card| card.to_string()),
+        Some("QD".to_string())
+    );
+}
+
+#[test]
+fn test_board_pair_risk() {
+    assert_eq!(board_pair_risk(&cards("KH 8D 4C")), PairRisk::None);
+    assert_eq!(board_pair_risk(&cards("KH 8D 8C 2S")), PairRisk::Paired);
+    assert_eq!(
+        board_pair_risk(&cards("KH 8D 8C KS 2H")),
+        PairRisk::DoublePaired
+    );
+    assert_eq!(board_pair_risk(&cards("AH AD AC")), PairRisk::Tripled);
+}
+
+#[test]
+fn test_ascii_art() {
+    let art = PokerHand::new("10H 2S JD QC 4H").ascii_art();
+    assert_eq!(art.lines().count(), 5);
+    for (rank, symbol) in [("10", '♥'), ("2", '♠'), ("J", '♦'), ("Q", '♣'), ("4", '♥')] {
+        assert!(art.contains(rank));
+        assert!(art.contains(symbol));
+    }
+    assert_eq!(
+        art.lines().nth(1),
+        Some("|2    | |4    | |10   | |J    | |Q    |")
+    );
+}
+
+#[test]
+fn test_is_drawing_dead() {
+    let board: [Card; 5] = cards("KH 8H 4H 2C 7S").try_into().unwrap();
+    let opponent = PokerHand::new("KS KD KH 8H 4H");
+    assert!(is_drawing_dead(&cards("QS QD"), &board, &opponent));
+    assert!(!is_drawing_dead(&cards("5H 6H"), &board, &opponent));
+}
+
+#[test]
+fn test_possible_straights() {
+    // 3 to 7, 4 to 8, 5 to 9 and 6 to 10
+    let ranks = [
+        CardRank::Five,
+        CardRank::Six,
+        CardRank::Seven,
+        CardRank::Eight,
+    ];
+    assert_eq!(possible_straights(&ranks), 4);
+
+    // only the Ace to 5 straight
+    let ranks = [
+        CardRank::Ace,
+        CardRank::Two,
+        CardRank::Three,
+        CardRank::Nine,
+    ];
+    assert_eq!(possible_straights(&ranks), 1);
+
+    let ranks = [CardRank::Two, CardRank::Eight, CardRank::King];
+    assert_eq!(possible_straights(&ranks), 0);
+}
+
+#[test]
+fn test_is_freeroll() {
+    // both hold the Jack high straight, but only hearts can make a flush
+    let board = cards("9H 8H 7C 2S");
+    assert_eq!(
+        is_freeroll(&cards("JH 10H"), &cards("JD 10D"), &board),
+        Some(FreerollDirection::A)
+    );
+    assert_eq!(
+        is_freeroll(&cards("JD 10D"), &cards("JH 10H"), &board),
+        Some(FreerollDirection::B)
+    );
+    assert_eq!(
+        is_freeroll(&cards("JC 10S"), &cards("JD 10D"), &board),
+        None
+    );
+}
+
+#[test]
+fn test_winning_hands_with_ranking() {
+    let hands = ["2S 4S 5S 6S 7S", "3H 3D 3C 8H 8D"];
+    assert_eq!(
+        winning_hands_with_ranking(&hands, &CategoryRanking::default()),
+        vec!["3H 3D 3C 8H 8D"]
+    );
+
+    let short_deck = CategoryRanking::default()
+        .with_index(PokerHandRank::Flush, 7)
+        .with_index(PokerHandRank::FullHouse, 6);
+    assert_eq!(
+        winning_hands_with_ranking(&hands, &short_deck),
+        vec!["2S 4S 5S 6S 7S"]
+    );
+}
+
+#[test]
+fn test_backdoor_draws() {
+    let hole: [Card; 2] = cards("AH 7H").try_into().unwrap();
+    let flop: [Card; 3] = cards("KH 2C QS").try_into().unwrap();
+    assert_eq!(
+        backdoor_draws(&hole, &flop),
+        BackdoorInfo {
+            flush: true,
+            straight: true
+        }
+    );
+
+    let hole: [Card; 2] = cards("9S 8D").try_into().unwrap();
+    let flop: [Card; 3] = cards("2H 7C KH").try_into().unwrap();
+    assert_eq!(
+        backdoor_draws(&hole, &flop),
+        BackdoorInfo {
+            flush: false,
+            straight: true
+        }
+    );
+
+    let hole: [Card; 2] = cards("9S 2D").try_into().unwrap();
+    let flop: [Card; 3] = cards("5H KC KH").try_into().unwrap();
+    assert_eq!(
+        backdoor_draws(&hole, &flop),
+        BackdoorInfo {
+            flush: false,
+            straight: false
+        }
+    );
+}
+
+#[test]
+fn test_class_label() {
+    assert_eq!(
+        PokerHand::new("2S 4S 6S 8S AS").class_label(),
+        "FlushAceHigh"
+    );
+    assert_eq!(
+        PokerHand::new("AS AH AD KC KD").class_label(),
+        "FullHouseAcesOverKings"
+    );
+    assert_eq!(
+        PokerHand::new("6S 6H 3D 4C 8D").class_label(),
+        "PairOfSixes"
+    );
+}
+
+#[test]
+fn test_validate_multideck() {
+    assert_eq!(validate_multideck(&cards("AS AS KH KH 2C"), 2), Ok(()));
+
+    let error = validate_multideck(&cards("AS KH AS 2C AS"), 2).unwrap_err();
+    assert_eq!(error.to_string(), "Card AS appears 3 times");
+}
+
+#[test]
+fn test_strength_delta_sign() {
+    let pairs = [
+        ("4S 5S 7H 8D JC", "2S 4C 7S 9H 10H"),
+        ("2S 8H 2D 8D 3H", "4S 5H 4C 8S 5D"),
+        ("4D AH 3S 2D 5C", "4S 6H 7S 8D 5H"),
+        ("2H 3H 4H 5H 6H", "2S 3S 4S 5S 6S"),
+        ("3S 3H 2S 3D 3C", "3S 3H 4S 3D 3C"),
+    ];
+    for (a, b) in pairs {
+        let delta = PokerHand::new(a).strength_delta(&PokerHand::new(b));
+        match winning_hands(&[a, b])[..] {
+            [winner] if winner == a => assert!(delta > 0, "{} beats {}", a, b),
+            [winner] if winner == b => assert!(delta < 0, "{} beats {}", b, a),
+            _ => assert_eq!(delta, 0, "{} ties {}", a, b),
+        }
+    }
+}
+
+#[test]
+fn test_classify_from_bits() {
+    let classify = |hand: &str| {
+        let hand = cards(hand);
+        let mut rank_bits = 0u16;
+        let mut rank_counts = [0u8; 15];
+        for card in &hand {
+            let rank = card.rank().as_number();
+            rank_bits |= 1 << rank;
+            rank_counts[rank as usize] += 1;
+        }
+        let suites: Vec<char> = hand
+            .iter()
+            .map(|card| card.to_string().pop().unwrap())
+            .collect();
+        let is_flush = suites.iter().all(|&suite| suite == suites[0]);
+        classify_from_bits(rank_bits, is_flush, &rank_counts)
+    };
+
+    let ranks = [
+        "A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A",
+    ];
+    let mut hands: Vec<String> = vec![];
+    for low in 0..10 {
+        for suites in [["S", "H", "D", "C", "S"], ["H"; 5]] {
+            let hand: Vec<String> = (0..5)
+                .map(|i| format!("{}{}", ranks[low + i], suites[i]))
+                .collect();
+            hands.push(hand.join(" "));
+        }
+    }
+    hands.extend(
+        [
+            "2S 4S 5S 6S 7S",
+            "3H 3D 3C 8H 8D",
+            "3H 3D 3C 3S 8D",
+            "3H 3D 3C 9S 8D",
+            "3H 3D 9C 9S 8D",
+            "3H 3D 9C 10S 8D",
+            "AH 3D 9C 10S 8D",
+            "KS AH 2D 3C 4S",
+        ]
+        .map(String::from),
+    );
+
+    for hand in &hands {
+        assert_eq!(classify(hand), PokerHand::new(hand).rank(), "{}", hand);
+    }
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_race_favorite() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // a pair of Queens against Ace King is a bit better than a coin flip
+    let mut rng = StdRng::seed_from_u64(5);
+    match race_favorite(&cards("QS QH"), &cards("AD KC"), &[], &mut rng, 2000) {
+        RaceResult::AFavored(margin) => assert!(margin > 0.0 && margin < 0.3),
+        result => panic!("Expected the pair to be favored, got {:?}", result),
+    }
+}
+
+#[test]
+fn test_distinct_ranks() {
+    assert_eq!(
+        distinct_ranks(&cards("AS 9H 2D 9C KS 2H 5C")),
+        vec![
+            CardRank::Two,
+            CardRank::Five,
+            CardRank::Nine,
+            CardRank::King,
+            CardRank::Ace
+        ]
+    );
+}
+
+#[test]
+fn test_summary_of_two_pair() {
+    let summary = PokerHand::new("4S 9H 4D 9C KS").summary();
+    assert_eq!(summary.rank, PokerHandRank::TwoPairs);
+    assert_eq!(
+        summary
+            .scoring_cards
+            .iter()
+            .map(|card| card.to_string())
+            .collect::<Vec<_>>(),
+        vec!["9H", "9C", "4S", "4D"]
+    );
+    assert_eq!(summary.kickers.len(), 1);
+    assert_eq!(summary.kickers[0].to_string(), "KS");
+}
+
+#[test]
+fn test_qualifies_bad_beat() {
+    let quads = PokerHand::new("2S 2H 2D 2C KS");
+    let straight_flush = PokerHand::new("5H 6H 7H 8H 9H");
+    assert!(qualifies_bad_beat(
+        &quads,
+        &straight_flush,
+        PokerHandRank::FourOfAKind
+    ));
+    assert!(!qualifies_bad_beat(
+        &straight_flush,
+        &quads,
+        PokerHandRank::FourOfAKind
+    ));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_chop_probability() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // the same ranks only differ when one of them makes a flush
+    let mut rng = StdRng::seed_from_u64(9);
+    let chop = chop_probability(&cards("AS KD"), &cards("AH KC"), &[], 1000, &mut rng);
+    assert!(chop > 0.9, "Expected mostly chopped pots, got {}", chop);
+}
+
+#[test]
+fn test_straight_flush_outs() {
+    let outs: Vec<String> = straight_flush_outs(&cards("6H 7H 8H 9H"))
+        .iter()
+        .map(|card| card.to_string())
+        .collect();
+    assert_eq!(outs, vec!["5H", "10H"]);
+}
+
+#[test]
+fn test_playability_weights_outs() {
+    // both make the same Ace high, but only the first one draws to a flush
+    let board = cards("KH 9H 2C");
+    let (flush_draw, no_draw) = (cards("AH 3H"), cards("AS 3D"));
+    let made = |hole: &[Card]| {
+        let known: Vec<Card> = hole.iter().chain(&board).copied().collect();
+        best_of(&known).percentile()
+    };
+    assert_eq!(made(&flush_draw), made(&no_draw));
+    assert!(improvement_outs(&flush_draw, &board).len() > improvement_outs(&no_draw, &board).len());
+    assert!(playability(&flush_draw, &board) > playability(&no_draw, &board));
+    assert!(playability(&no_draw, &board) > made(&no_draw));
+}
+
+#[test]
+fn test_should_slow_down_on_monotone_board() {
+    assert_eq!(board_texture(&cards("2H 7H KH")), BoardTexture::Monotone);
+    assert!(should_slow_down(&cards("2H 7H KH")));
+}
+
+#[test]
+fn test_should_not_slow_down_on_dry_board() {
+    assert_eq!(board_texture(&cards("2C 7D KS")), BoardTexture::Rainbow);
+    assert!(!should_slow_down(&cards("2C 7D KS")));
+}
+
+#[test]
+fn test_should_slow_down_on_four_straight_board() {
+    assert!(should_slow_down(&cards("5C 6D 8S 9H")));
+}
+
+#[test]
+fn test_twoplustwo_index() {
+    assert_eq!(Card::new("2C").to_twoplustwo_index(), 1);
+    assert_eq!(Card::new("2S").to_twoplustwo_index(), 4);
+    assert_eq!(Card::new("3C").to_twoplustwo_index(), 5);
+    assert_eq!(Card::new("10H").to_twoplustwo_index(), 35);
+    assert_eq!(Card::new("AS").to_twoplustwo_index(), 52);
+}
+
+#[test]
+fn test_combos_remaining() {
+    let range = parse_range("AA, AKs, AKo").unwrap();
+    assert_eq!(combos_remaining(&range, &[]), 6 + 4 + 12);
+
+    let aces = parse_range("AA").unwrap();
+    assert_eq!(combos_remaining(&aces, &cards("AS")), 3);
+}
+
+#[test]
+fn test_is_effective_nuts() {
+    let board = cards("QH 7H 2H 9C 4D");
+    let capped = parse_range("QQ, 99, 77, AKo").unwrap();
+    assert!(is_effective_nuts(&cards("6H 5H"), &board, &capped));
+
+    let with_nut_flush = parse_range("AKs").unwrap();
+    assert!(!is_effective_nuts(&cards("6H 5H"), &board, &with_nut_flush));
+}
+
+#[test]
+fn test_min_defense_frequency() {
+    assert!((min_defense_frequency(100.0, 100.0) - 0.5).abs() < 1e-9);
+    assert!((min_defense_frequency(50.0, 100.0) - 2.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_steel_wheel_is_the_lowest_straight_flush() {
+    let hand: [Card; 5] = cards("4S AS 3S 2S 5S").try_into().unwrap();
+    let sequence = PokerHand::get_sequence(hand).unwrap();
+    assert!(sequence.is_steel_wheel(CardSuite::Spades));
+    assert!(!sequence.is_steel_wheel(CardSuite::Hearts));
+
+    assert_eq!(
+        winning_hands(&["4S AS 3S 2S 5S", "6H 5H 4H 3H 2H"]),
+        vec!["6H 5H 4H 3H 2H"]
+    );
+}
+
+#[test]
+fn test_evaluate_seven() {
+    let seven: [Card; 7] = cards("AS KD 9H 9C KS 2H 5C").try_into().unwrap();
+    let best = best_of(&seven);
+    assert_eq!(evaluate_seven(&seven), (best.rank(), best.strength_key()));
+    assert_eq!(evaluate_seven(&seven).0, PokerHandRank::TwoPairs);
+}
+
+#[test]
+fn test_card_from_str_round_trips() {
+    for card in unseen_cards(&[]) {
+        let parsed = card.to_string().parse::<Card>();
+        assert_eq!(parsed, Ok(card));
+        assert_eq!(parsed.unwrap().to_string(), card.to_string());
+    }
+    assert_eq!("10D".parse::<Card>().unwrap().to_string(), "10D");
+    assert_eq!(
+        "AH ".parse::<Card>(),
+        Err(CardParseError::TrailingCharacters("AH ".to_string()))
+    );
+}
+
+#[test]
+fn test_card_rank_try_from_number() {
+    assert_eq!(CardRank::try_from(1), Ok(CardRank::LowAce));
+    assert_eq!(CardRank::try_from(10), Ok(CardRank::Ten));
+    assert_eq!(CardRank::try_from(14), Ok(CardRank::Ace));
+    assert_eq!(CardRank::try_from(0), Err(InvalidRankNumber(0)));
+    assert_eq!(CardRank::try_from(15), Err(InvalidRankNumber(15)));
+
+    assert_eq!(CardRank::from_number(0), CardRank::Ace);
+    assert_eq!(CardRank::from_number(15), CardRank::LowAce);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_showdown_json() {
+    let hands = ["4S 5H 4C 8D 4H", "10S 9H 8C 7D 6H", "2S AH 3C 5D 4H"];
+    let json: serde_json::Value = serde_json::from_str(&showdown_json(&hands)).unwrap();
+    let winners = winning_hands(&hands);
+    let entries = json["hands"].as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    for (entry, hand) in entries.iter().zip(hands) {
+        assert_eq!(entry["hand"], hand);
+        assert_eq!(entry["winner"], winners.contains(&hand));
+        assert_eq!(entry["strength_key"], PokerHand::new(hand).strength_key());
+    }
+    assert_eq!(entries[1]["category"], "Straight, Ten high");
+}
+
+#[test]
+fn test_dominates() {
+    assert!(dominates(
+        (CardRank::Ace, CardRank::King),
+        (CardRank::Queen, CardRank::Ace)
+    ));
+    assert!(!dominates(
+        (CardRank::Ace, CardRank::King),
+        (CardRank::Queen, CardRank::Jack)
+    ));
+    assert!(!dominates(
+        (CardRank::Ace, CardRank::Queen),
+        (CardRank::Ace, CardRank::King)
+    ));
+}
+
+#[test]
+fn test_card_suite_accessor() {
+    let hand = cards("4S 4H 9S QH 2S");
+    assert_eq!(hand[1].suite(), &CardSuite::Hearts);
+
+    let suites: std::collections::HashSet<(CardRank, CardSuite)> = hand
+        .iter()
+        .chain(&cards("4S"))
+        .map(|card| (*card.rank(), *card.suite()))
+        .collect();
+    assert_eq!(suites.len(), 5);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_range_vs_range() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let tight = parse_range("AA, KK, QQ, AKs").unwrap();
+    let wide = parse_range("72o, 83o, 94o, J5s, Q3o, 64s").unwrap();
+    let mut rng = StdRng::seed_from_u64(11);
+    let (win, tie, lose) =
+        range_vs_range(&tight, &wide, &cards("2C 8D KS"), 500, &mut rng).unwrap();
+    assert!(
+        win > lose,
+        "Expected the tight range to be favored, got {}",
+        win
+    );
+    assert!((win + tie + lose - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_card_equality_compares_suites() {
+    let deck = unseen_cards(&[]);
+    for (i, a) in deck.iter().enumerate() {
+        for b in &deck[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+    assert_ne!(Card::new("AH"), Card::new("AS"));
+    assert!(Card::new("AH").same_rank(&Card::new("AS")));
+
+    let unique: std::collections::HashSet<Card> = cards("AH AS AH").into_iter().collect();
+    assert_eq!(unique.len(), 2);
+}
+
+#[test]
+fn test_from_cards() {
+    let hand: OwnedPokerHand = PokerHand::from_cards(cards("4S 4H 9D QC 2D").try_into().unwrap());
+    assert_eq!(hand.rank(), PokerHandRank::OnePair);
+    assert_eq!(hand.to_string(), "\"2D 4S 4H 9D QC\"");
+    assert_eq!(
+        hand.strength_key(),
+        PokerHand::new("4S 4H 9D QC 2D").strength_key()
+    );
+}
+
+#[test]
+fn test_poker_hand_rank_accessor() {
+    assert_eq!(
+        PokerHand::new("2H 2D 2S 2C 3H").rank(),
+        PokerHandRank::FourOfAKind
+    );
+}
+
+#[test]
+fn test_poker_hand_ord_includes_kickers() {
+    assert!(PokerHand::new("KS KH 9D 4C 2D") > PokerHand::new("KD KC 8D 4H 2S"));
+    assert!(PokerHand::new("KS KH 9D 4C 2D") == PokerHand::new("KD KC 9H 4S 2C"));
+    assert!(PokerHand::new("4D AH 3S 2D 5C") < PokerHand::new("2S 3H 4C 5D 6H"));
+
+    let mut hands = [
+        PokerHand::new("2S 3H 4C 5D 6H"),
+        PokerHand::new("KS KH 9D 4C 2D"),
+        PokerHand::new("4D AH 3S 2D 5C"),
+        PokerHand::new("KD KC 8D 4H 2S"),
+    ];
+    hands.sort();
+    let sorted: Vec<String> = hands.iter().map(|hand| hand.to_string()).collect();
+    assert_eq!(
+        sorted,
+        vec![
+            "\"KD KC 8D 4H 2S\"",
+            "\"KS KH 9D 4C 2D\"",
+            "\"4D AH 3S 2D 5C\"",
+            "\"2S 3H 4C 5D 6H\"",
+        ]
+    );
+}
+
+#[test]
+fn test_winning_hands_indices_with_identical_hands() {
+    let first = String::from("4S 4H 9D QC 2D");
+    let second = first.clone();
+    let input = [first.as_str(), "3S 3H 9C QD 2C", second.as_str()];
+    assert_eq!(winning_hands_indices(&input), vec![0, 2]);
+    assert_eq!(winning_hands_indices(&["2S 3H 4C 5D 6H"]), vec![0]);
+}
+
+#[test]
+fn test_full_house_tie_break_compares_the_pair() {
+    assert_eq!(
+        winning_hands(&["6S 6H 6D KS KH", "6S 6H 6D QS QH"]),
+        vec!["6S 6H 6D KS KH"]
+    );
+    assert_eq!(
+        winning_hands(&["6S 6H 6D KS KH", "6C 6H 6D KD KC"]),
+        vec!["6S 6H 6D KS KH", "6C 6H 6D KD KC"]
+    );
+}
+
+#[test]
+fn test_standard_deck() {
+    let deck = standard_deck();
+    assert_eq!(deck.len(), 52);
+    for (i, a) in deck.iter().enumerate() {
+        assert!(deck[i + 1..].iter().all(|b| a != b));
+    }
+    assert_eq!(deck[0].to_string(), "2C");
+    assert_eq!(deck[12].to_string(), "AC");
+    assert_eq!(deck[51].to_string(), "AS");
+}
+
+#[test]
+fn test_all_ranks_and_suites() {
+    assert_eq!(CardRank::all().count(), 13);
+    assert!(CardRank::all().all(|rank| rank != CardRank::LowAce));
+    assert_eq!(CardRank::all().next(), Some(CardRank::Two));
+    assert_eq!(CardRank::all().last(), Some(CardRank::Ace));
+    assert_eq!(
+        CardSuite::all().collect::<Vec<_>>(),
+        vec![
+            CardSuite::Clubs,
+            CardSuite::Diamonds,
+            CardSuite::Hearts,
+            CardSuite::Spades
+        ]
+    );
+}
+
+#[test]
+fn test_poker_hand_rank_display() {
+    assert_eq!(PokerHandRank::HighCard.to_string(), "High Card");
+    assert_eq!(PokerHandRank::OnePair.to_string(), "One Pair");
+    assert_eq!(PokerHandRank::ThreeOfAKind.to_string(), "Three of a Kind");
+    assert_eq!(PokerHandRank::RoyalFlush.to_string(), "Royal Flush");
+
+    let hand = PokerHand::new("3S 3H 3D KS KH");
+    assert_eq!(
+        format!("{}: {}", hand, hand.rank()),
+        "\"3S 3H 3D KS KH\": Full House"
+    );
+    assert_eq!(format!("{:?}", hand.rank()), "FullHouse");
+}
+
+#[test]
+fn test_poker_hand_rank_from_str_round_trips() {
+    for n in 1..=10 {
+        let rank = PokerHandRank::from_number(n);
+        assert_eq!(rank.to_string().parse::<PokerHandRank>(), Ok(rank));
+    }
+    assert_eq!(
+        "four OF a kind".parse::<PokerHandRank>(),
+        Ok(PokerHandRank::FourOfAKind)
+    );
+    assert_eq!(
+        "Five of a Kind".parse::<PokerHandRank>(),
+        Err(UnknownHandRank("Five of a Kind".to_string()))
+    );
+}
+
+#[test]
+fn test_ten_written_as_t() {
+    assert_eq!(Card::new("TS"), Card::new("10S"));
+    assert_eq!(
+        PokerHand::new("AS KS QS JS TS").rank(),
+        PokerHandRank::RoyalFlush
+    );
+}
+
+#[test]
+fn test_alternate_display_uses_suite_symbols() {
+    assert_eq!(format!("{:#}", Card::new("AH")), "A♥");
+    assert_eq!(format!("{}", Card::new("AH")), "AH");
+    assert_eq!(format!("{:#}", CardSuite::Clubs), "♣");
+    assert_eq!(
+        format!("{:#}", PokerHand::new("10S JD QC 2H 5S")),
+        "\"10♠ J♦ Q♣ 2♥ 5♠\""
+    );
+}
+
+#[test]
+fn test_parse_suite_symbols() {
+    assert_eq!(Card::new("A♠"), Card::new("AS"));
+    assert_eq!(Card::new("10♦"), Card::new("10D"));
+    assert_eq!(
+        PokerHand::new("2♥ 7H 9♥ JH K♥").rank(),
+        PokerHandRank::Flush
+    );
+}
+
+#[test]
+fn test_parse_cards_ignoring_case() {
+    assert_eq!(Card::new("ah"), Card::new("AH"));
+    assert_eq!(Card::new("aH"), Card::new("AH"));
+    assert_eq!(Card::new("10c"), Card::new("10C"));
+    assert_eq!(Card::new("ts"), Card::new("10S"));
+
+    let upper = PokerHand::new("QD QH 10C 10S 2D");
+    let lower = PokerHand::new("qd qh 10c 10s 2d");
+    let mixed = PokerHand::new("Qd qH 10c 10S 2d");
+    assert_eq!(upper.strength_key(), lower.strength_key());
+    assert_eq!(upper.strength_key(), mixed.strength_key());
+    assert!(lower.same_cards(&mixed));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trips() {
+    let deck = standard_deck();
+    let json = serde_json::to_string(&deck).unwrap();
+    assert!(json.starts_with("[\"2C\",\"3C\""));
+    assert_eq!(serde_json::from_str::<Vec<Card>>(&json).unwrap(), deck);
+
+    let hand: Vec<Card> = serde_json::from_str(r#"["AH","KH","QH","JH","10H"]"#).unwrap();
+    assert_eq!(hand, cards("AH KH QH JH 10H"));
+    assert_eq!(
+        serde_json::to_string(&(CardRank::Ten, CardSuite::Hearts)).unwrap(),
+        r#"["10","H"]"#
+    );
+    let rank: PokerHandRank = serde_json::from_str(r#""Full House""#).unwrap();
+    assert_eq!(rank, PokerHandRank::FullHouse);
+    assert!(serde_json::from_str::<Card>(r#""1H""#).is_err());
+}
+
+#[test]
+fn test_card_from_parts() {
+    assert_eq!(
+        Card::from_parts(CardRank::Ace, CardSuite::Spades),
+        Card::new("AS")
+    );
+    assert_eq!(
+        Card::from_parts(CardRank::Ten, CardSuite::Hearts).to_string(),
+        "10H"
+    );
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_shuffle_and_deal() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let deal = |seed| {
+        let mut deck = Deck::new();
+        deck.shuffle(&mut StdRng::seed_from_u64(seed));
+        let hand = deck.deal(5).unwrap();
+        assert_eq!(deck.len(), 47);
+        hand
+    };
+    assert_eq!(deal(3), deal(3));
+    assert_ne!(deal(3), deal(4));
+
+    let mut deck = Deck::new();
+    assert!(deck.deal(53).is_none());
+    assert_eq!(deck.len(), 52);
+}
+
+#[test]
+fn test_kickers() {
+    let names = |hand: &str| -> Vec<String> {
+        PokerHand::new(hand)
+            .kickers()
+            .iter()
+            .map(|card| card.to_string())
+            .collect()
+    };
+    assert_eq!(names("4S 8H 9D QC 2D"), vec!["QC", "9D", "8H", "4S", "2D"]);
+    assert_eq!(names("7S 7H 7D 7C 3D"), vec!["3D"]);
+    assert!(names("3S 3H 3D KS KH").is_empty());
+}
+
+#[test]
+fn test_one_is_not_a_rank() {
+    assert_eq!(
+        Card::try_new("1H"),
+        Err(CardParseError::InvalidRank("1H".to_string()))
+    );
+    assert_eq!(CardRank::from_number(1), CardRank::LowAce);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+#[should_panic(expected = "At least one sample is needed")]
+fn test_win_rate_vs_random_without_samples() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let hand: [Card; 5] = cards("AS AH KD KC 2S").try_into().unwrap();
+    win_rate_vs_random(&hand, 0, &mut StdRng::seed_from_u64(1));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+#[should_panic(expected = "At least one sample is needed")]
+fn test_category_distribution_without_samples() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    category_distribution(&cards("AS AH"), 0, &mut StdRng::seed_from_u64(1));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_deal_and_evaluate_too_many_players() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(7);
+    assert_eq!(
+        deal_and_evaluate(10, &mut rng).map(|hands| hands.len()),
+        Some(10)
+    );
+    assert!(deal_and_evaluate(11, &mut rng).is_none());
+    assert!(deal_and_evaluate(usize::MAX, &mut rng).is_none());
+}
+
+#[test]
+fn test_parse_range_tens_are_t() {
+    assert_eq!(
+        parse_range("ATo").unwrap(),
+        vec![(CardRank::Ace, CardRank::Ten, false)]
+    );
+    assert_eq!(
+        parse_range("A10o"),
+        Err(RangeParseError::InvalidRank("A10o".to_string()))
+    );
+}
+
+#[test]
+fn test_hole_cards_label() {
+    assert_eq!(hole_cards_label(&cards("KH AH")), "AhKh");
+    assert_eq!(hole_cards_label(&cards("10C 9D")), "Tc9d");
+    assert_eq!(hole_cards_label(&cards("AH AS")), "AsAh");
+}
+
+#[test]
+fn test_board_texture() {
+    assert_eq!(board_texture(&cards("2H 7H KH")), BoardTexture::Monotone);
+    assert_eq!(
+        board_texture(&cards("2H 7H KH 9C 4D")),
+        BoardTexture::FlushPossible
+    );
+    assert_eq!(board_texture(&cards("2H 7H KC")), BoardTexture::TwoTone);
+    assert_eq!(board_texture(&cards("2H 7S KC 9D")), BoardTexture::Rainbow);
+    assert!(should_slow_down(&cards("2H 7H KH 9C 4D")));
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_range_vs_range_without_deals() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let aces = parse_range("AA").unwrap();
+    let mut rng = StdRng::seed_from_u64(11);
+    assert_eq!(range_vs_range(&aces, &aces, &[], 0, &mut rng), None);
+    // with three aces on the board, neither range has hole cards left
+    let board = cards("AS AH AD");
+    assert_eq!(range_vs_range(&aces, &aces, &board, 50, &mut rng), None);
+}
+
+#[test]
+fn test_triplets_compare_by_rank() {
+    let triplet = |hand: &str| PokerHand::get_triplet(cards(hand).try_into().unwrap()).unwrap();
+    let (spades, clubs) = (triplet("6S 6H 6D KS QH"), triplet("6C 6H 6D KS QH"));
+    assert_eq!(spades, clubs);
+    assert_eq!(spades.cmp(&clubs), std::cmp::Ordering::Equal);
+    assert!(triplet("7C 7H 7D KS QH") > spades);
+    assert_eq!(
+        PokerHand::new("6S 6H 6D KS QH").cmp(&PokerHand::new("6C 6H 6D KS QH")),
+        std::cmp::Ordering::Equal
+    );
+}
+
+#[test]
+fn test_winning_hands_agree_with_ord() {
+    let input = &[
+        "4S 4H 9D QC 2D",
+        "4D 4C 9S QH 3S",
+        "AC 2D 3H 4S 5C",
+        "KS KH 5C 5D 2H",
+        "KC KD 5H 5S 3C",
+    ];
+    let best = input.iter().map(|hand| PokerHand::new(hand)).max().unwrap();
+    let expected: Vec<&str> = input
+        .iter()
+        .copied()
+        .filter(|hand| PokerHand::new(hand) == best)
+        .collect();
+    assert_eq!(winning_hands(input), expected);
+    assert_eq!(winning_hands(input), vec!["AC 2D 3H 4S 5C"]);
 }